@@ -49,12 +49,18 @@ pub fn include(input: TokenStream) -> TokenStream {
     std::fs::rename(&wasm_pack_output.path_to_built_js, &final_path_to_built_js)
         .expect("Error moving the built JS file to the final location.");
 
+    crate::stale_built_assets::register_output(&final_path_to_built_wasm);
+    crate::stale_built_assets::register_output(&final_path_to_built_js);
+
     let minified_js_string = if input.production {
         overwrite_js_with_minified(final_path_to_built_js)
     } else {
         std::fs::read_to_string(&final_path_to_built_js).expect("Error reading JS file.")
     };
 
+    let js_gzipped_size_in_bytes =
+        crate::compression::gzipped_size_in_bytes(minified_js_string.as_bytes());
+
     let wasm_bytes =
         std::fs::read(&final_path_to_built_wasm).expect("Error reading the built wasm file.");
 
@@ -68,7 +74,9 @@ pub fn include(input: TokenStream) -> TokenStream {
             input.js_url_path,
             minified_js_string,
             input.js_performance_budget,
+            js_gzipped_size_in_bytes,
         ),
+        critical: input.critical,
     };
 
     // Clean up the temporary directory where wasm-pack saved the built files.
@@ -202,6 +210,7 @@ struct IncludeBrowserCrateInput {
     wasm_url_path: PathBuf,
     wasm_performance_budget: Duration,
 
+    critical: bool,
     production: bool,
     debug: bool,
 
@@ -220,6 +229,7 @@ include_browser_crate!(
     js_performance_budget: 200,
     wasm_url_path: \"browser_bg.wasm\",
     wasm_performance_budget: 200,
+    critical: true,
     production: true,
     debug: true,
 );
@@ -250,6 +260,9 @@ include_browser_crate!(
         let wasm_performance_budget = Duration::from_millis(wasm_performance_budget_millis);
         // eprintln!("wasm_performance_budget: {:?}", wasm_performance_budget);
 
+        // Critical browser crates get preloaded. See BrowserCrateAsset::resource_hints.
+        let critical = parse_named_bool_argument("critical", &input).unwrap_or(false);
+
         // True if we're in release mode (i.e. `cargo build --release`).
         let release_mode = !cfg!(debug_assertions);
         // Default to production: true in release mode.
@@ -268,6 +281,7 @@ include_browser_crate!(
             js_performance_budget,
             wasm_url_path: PathBuf::from(wasm_url_path),
             wasm_performance_budget,
+            critical,
             production,
             debug,
             span: input_span,
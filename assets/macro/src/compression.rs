@@ -0,0 +1,10 @@
+use flate2::{write::GzEncoder, Compression};
+use std::io::Write;
+
+// How big something is over the wire, assuming the server gzips it.
+// Brotli usually does a bit better, so this errs on the large side.
+pub fn gzipped_size_in_bytes(bytes: &[u8]) -> usize {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(bytes).expect("Error gzipping asset.");
+    encoder.finish().expect("Error gzipping asset.").len()
+}
@@ -0,0 +1,185 @@
+use std::sync::OnceLock;
+
+// Project-wide settings for the asset macros, read from `assets.toml`
+// in the workspace root. Every setting is optional, and the file itself
+// can be missing.
+pub struct AssetsConfig {
+    pub captioning: Option<CaptioningConfig>,
+    pub images: ImagesConfig,
+    pub tailwind: TailwindConfig,
+}
+
+pub struct CaptioningConfig {
+    // The program to run and its arguments. The absolute path to the
+    // image is appended as the last argument, and the program should
+    // print the caption to stdout.
+    pub command: Vec<String>,
+}
+
+// Defaults for `include_image!` and `include_images_in_folder!`,
+// which each image can override.
+#[derive(Default)]
+pub struct ImagesConfig {
+    pub widths: Option<Vec<u32>>,
+    pub densities: Option<Vec<u32>>,
+    pub max_width: Option<u32>,
+    pub quality: Option<u8>,
+}
+
+// Used by `class!` to check class names against our Tailwind build.
+#[derive(Default)]
+pub struct TailwindConfig {
+    // Relative to the workspace root, like the argument to `include_tailwind!`.
+    pub path_to_input_file: Option<String>,
+    // Classes that are fine to use even though Tailwind doesn't generate
+    // them, like ones only used as hooks for JavaScript.
+    pub allowed_classes: Vec<String>,
+}
+
+// Proc macros are loaded once per crate that uses them,
+// so we only read the config once per compile.
+static CONFIG: OnceLock<AssetsConfig> = OnceLock::new();
+
+pub fn config() -> &'static AssetsConfig {
+    CONFIG.get_or_init(read_config)
+}
+
+fn read_config() -> AssetsConfig {
+    let config_path = assets_runtime::paths::assets_config_path();
+
+    let table = match std::fs::read_to_string(&config_path) {
+        Ok(contents) => contents
+            .parse::<toml::Table>()
+            .unwrap_or_else(|error| panic!("Error parsing {}: {}", config_path.display(), error)),
+        Err(error) => {
+            log::info!(
+                "Couldn't read {} so using the default config. Original error message: {}",
+                config_path.display(),
+                error
+            );
+            toml::Table::new()
+        }
+    };
+
+    AssetsConfig {
+        captioning: parse_captioning_config(&table),
+        images: parse_images_config(&table),
+        tailwind: parse_tailwind_config(&table),
+    }
+}
+
+fn parse_captioning_config(table: &toml::Table) -> Option<CaptioningConfig> {
+    let captioning = table.get("captioning")?.as_table()?;
+
+    let command = captioning
+        .get("command")
+        .and_then(|command| command.as_array())
+        .expect("[captioning] in assets.toml needs a command, like command = [\"python3\", \"scripts/caption_image.py\"].")
+        .iter()
+        .map(|argument| {
+            argument
+                .as_str()
+                .expect("Every part of the captioning command in assets.toml should be a string.")
+                .to_string()
+        })
+        .collect::<Vec<String>>();
+
+    if command.is_empty() {
+        panic!("The captioning command in assets.toml can't be empty.");
+    }
+
+    Some(CaptioningConfig { command })
+}
+
+fn parse_images_config(table: &toml::Table) -> ImagesConfig {
+    let Some(images) = table.get("images").and_then(|images| images.as_table()) else {
+        return ImagesConfig::default();
+    };
+
+    let integer = |key: &str| {
+        images.get(key).map(|value| {
+            value
+                .as_integer()
+                .unwrap_or_else(|| panic!("{} in [images] in assets.toml should be a number.", key))
+        })
+    };
+
+    let integer_list = |key: &str| {
+        images.get(key).map(|value| {
+            value
+                .as_array()
+                .and_then(|values| {
+                    values
+                        .iter()
+                        .map(|value| {
+                            value
+                                .as_integer()
+                                .and_then(|value| u32::try_from(value).ok())
+                                .filter(|value| *value > 0)
+                        })
+                        .collect::<Option<Vec<u32>>>()
+                })
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} in [images] in assets.toml should be a list of positive numbers.",
+                        key
+                    )
+                })
+        })
+    };
+
+    let quality = integer("quality").map(|quality| match quality {
+        1..=100 => quality as u8,
+        _ => panic!("quality in [images] in assets.toml should be between 1 and 100."),
+    });
+
+    ImagesConfig {
+        widths: integer_list("widths"),
+        densities: integer_list("densities"),
+        max_width: integer("max_width").map(|max_width| {
+            u32::try_from(max_width)
+                .ok()
+                .filter(|max_width| *max_width > 0)
+                .unwrap_or_else(|| {
+                    panic!("max_width in [images] in assets.toml should be a positive number.")
+                })
+        }),
+        quality,
+    }
+}
+
+fn parse_tailwind_config(table: &toml::Table) -> TailwindConfig {
+    let Some(tailwind) = table
+        .get("tailwind")
+        .and_then(|tailwind| tailwind.as_table())
+    else {
+        return TailwindConfig::default();
+    };
+
+    let path_to_input_file = tailwind.get("path_to_input_file").map(|value| {
+        value
+            .as_str()
+            .expect("path_to_input_file in [tailwind] in assets.toml should be a string.")
+            .to_string()
+    });
+
+    let allowed_classes = tailwind
+        .get("allowed_classes")
+        .map(|value| {
+            value
+                .as_array()
+                .and_then(|values| {
+                    values
+                        .iter()
+                        .map(|value| value.as_str().map(|value| value.to_string()))
+                        .collect::<Option<Vec<String>>>()
+                })
+                .expect("allowed_classes in [tailwind] in assets.toml should be a list of strings.")
+        })
+        .unwrap_or_default();
+
+    TailwindConfig {
+        path_to_input_file,
+        allowed_classes,
+    }
+}
@@ -37,7 +37,8 @@ pub fn include(input: TokenStream) -> TokenStream {
 
     std::fs::create_dir_all(output_dir).expect("Error creating built assets dir.");
 
-    std::fs::copy(input_file_path, output_file_path).expect("Error copying file file.");
+    std::fs::copy(input_file_path, &output_file_path).expect("Error copying file file.");
+    crate::stale_built_assets::register_output(&output_file_path);
 
     let file_asset = FileAsset::new(
         input.url_path,
@@ -1,5 +1,6 @@
+use crate::font_subset::{subset_to_woff2, Subset};
 use crate::parse_macro_arguments::*;
-use assets_runtime::{paths::*, FontAsset};
+use assets_runtime::{paths::*, FontAsset, FontDisplay, FontFace, FontStyle};
 use proc_macro::TokenStream;
 use quote::quote;
 use std::str::FromStr;
@@ -25,10 +26,6 @@ pub fn include(input: TokenStream) -> TokenStream {
             .into();
     }
 
-    let size_in_bytes = std::fs::metadata(&input_file_path)
-        .expect("Error getting metadata for font file.")
-        .len();
-
     let output_file_path = output_file_path(&input.url_path);
 
     let output_dir = output_file_path
@@ -37,12 +34,53 @@ pub fn include(input: TokenStream) -> TokenStream {
 
     std::fs::create_dir_all(output_dir).expect("Error creating built assets dir.");
 
-    std::fs::copy(input_file_path, output_file_path).expect("Error copying font file.");
+    let size_in_bytes = match input.subset {
+        Some(subset) => {
+            let bytes = match subset_to_woff2(&input_file_path, subset) {
+                Ok(bytes) => bytes,
+                Err(message) => {
+                    return syn::Error::new(input.span, message)
+                        .to_compile_error()
+                        .into()
+                }
+            };
+
+            let original_size_in_bytes = std::fs::metadata(&input_file_path)
+                .expect("Error getting metadata for font file.")
+                .len();
+            log::info!(
+                "Subset font from {} to {} bytes.",
+                original_size_in_bytes,
+                bytes.len()
+            );
+
+            // Avoid touching the built file if it hasn't changed, so that
+            // file watchers don't see a change on every build.
+            let already_up_to_date = std::fs::read(&output_file_path)
+                .map(|existing_bytes| existing_bytes == bytes)
+                .unwrap_or(false);
+            if !already_up_to_date {
+                std::fs::write(&output_file_path, &bytes).expect("Error writing font file.");
+            }
+
+            bytes.len() as u64
+        }
+        None => {
+            std::fs::copy(&input_file_path, &output_file_path).expect("Error copying font file.");
+
+            std::fs::metadata(&input_file_path)
+                .expect("Error getting metadata for font file.")
+                .len()
+        }
+    };
+    crate::stale_built_assets::register_output(&output_file_path);
 
     let font_asset = FontAsset::new(
         input.url_path,
         input.performance_budget,
         size_in_bytes as usize,
+        input.face,
+        input.critical,
     );
 
     let output = quote! {
@@ -56,6 +94,10 @@ struct IncludeFontInput {
     path_to_input_file: PathBuf,
     url_path: PathBuf,
     performance_budget: Duration,
+    face: FontFace,
+    critical: bool,
+    // Fonts are copied as is without a subset.
+    subset: Option<Subset>,
     debug: bool,
     span: proc_macro2::Span,
 }
@@ -68,10 +110,26 @@ impl Parse for IncludeFontInput {
 
 include_font!(
     path_to_input_file: \"src/fonts/MyFont.otf\",
-    url_path: \"fonts/MyFont.otf\",
+    url_path: \"fonts/my-font.woff2\",
     performance_budget_millis: 300,
+    family: \"My Font\",
+    weight: 400,
+    style: normal,
+    display: swap,
+    critical: true,
+    subset: auto,
     debug: true,
 );
+
+family is required, and is what you use in CSS. weight defaults to 400,
+style to normal, and display to swap. Critical fonts are used above the
+fold, so they're preloaded. critical defaults to false.
+
+subset is optional, and can be latin or auto. latin keeps the characters
+used by English and most Western European languages. auto keeps the
+latin characters, plus any other characters used in html! templates
+across the workspace. Either way, the font is encoded as WOFF2, so url_path should end
+in .woff2. Without subset, the font is copied as is.
 "#;
 
         let error = syn::Error::new(input_span, error_message);
@@ -90,12 +148,76 @@ include_font!(
             parse_named_u64_argument("performance_budget_millis", &input).ok_or(error.clone())?;
         let performance_budget = Duration::from_millis(performance_budget_millis);
 
+        let family = parse_named_string_argument("family", &input).ok_or(error.clone())?;
+
+        let weight = match parse_named_u64_argument("weight", &input) {
+            Some(weight @ 1..=1000) => weight as u16,
+            Some(_) => return Err(syn::Error::new(
+                input_span,
+                "weight should be a number from 1 to 1000, like 400 for normal or 700 for bold.",
+            )),
+            None => 400,
+        };
+
+        let style = match parse_named_ident_argument("style", &input) {
+            None => FontStyle::Normal,
+            Some(ident) if ident == "normal" => FontStyle::Normal,
+            Some(ident) if ident == "italic" => FontStyle::Italic,
+            Some(ident) => {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "style should be normal or italic.",
+                ))
+            }
+        };
+
+        let display = match parse_named_ident_argument("display", &input) {
+            None => FontDisplay::Swap,
+            Some(ident) => match ident.to_string().as_str() {
+                "auto" => FontDisplay::Auto,
+                "block" => FontDisplay::Block,
+                "swap" => FontDisplay::Swap,
+                "fallback" => FontDisplay::Fallback,
+                "optional" => FontDisplay::Optional,
+                _ => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "display should be auto, block, swap, fallback, or optional.",
+                    ))
+                }
+            },
+        };
+
+        let critical = parse_named_bool_argument("critical", &input).unwrap_or(false);
+
+        let subset = parse_named_ident_argument("subset", &input)
+            .map(|ident| Subset::from_ident(&ident))
+            .transpose()?;
+
+        let is_woff2 = url_path
+            .extension()
+            .is_some_and(|extension| extension == "woff2");
+        if subset.is_some() && !is_woff2 {
+            return Err(syn::Error::new(
+                input_span,
+                "Subset fonts are encoded as WOFF2, so url_path should end in .woff2.",
+            ));
+        }
+
         let debug = parse_named_bool_argument("debug", &input).unwrap_or(false);
 
         Ok(IncludeFontInput {
             path_to_input_file,
             url_path,
             performance_budget,
+            face: FontFace {
+                family,
+                weight,
+                style,
+                display,
+            },
+            critical,
+            subset,
             debug,
             span: input_span,
         })
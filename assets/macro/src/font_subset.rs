@@ -0,0 +1,241 @@
+use assets_runtime::paths::*;
+use proc_macro2::{TokenStream, TokenTree};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use walkdir::WalkDir;
+
+// Bump this to invalidate every cached subset, e.g. after
+// changing the arguments we pass to pyftsubset.
+static CACHE_VERSION: &str = "1";
+
+// The same ranges Google Fonts uses for its latin subset. This covers
+// English and most Western European languages, plus common punctuation
+// and symbols like curly quotes and the euro sign.
+static LATIN_UNICODE_RANGES: &str = "U+0000-00FF,U+0131,U+0152-0153,U+02BB-02BC,U+02C6,U+02DA,U+02DC,U+2000-206F,U+2074,U+20AC,U+2122,U+2191,U+2193,U+2212,U+2215,U+FEFF,U+FFFD";
+
+/// Which glyphs to keep when subsetting a font.
+#[derive(Clone, Copy, Debug)]
+pub enum Subset {
+    Latin,
+    // The latin characters, plus any others that appear in our Maud
+    // templates. Text that's passed into templates as data, like project
+    // descriptions, isn't in the templates themselves, so the latin
+    // characters are always kept for it.
+    Automatic,
+}
+
+impl Subset {
+    pub fn from_ident(ident: &syn::Ident) -> syn::Result<Subset> {
+        match ident.to_string().as_str() {
+            "latin" => Ok(Subset::Latin),
+            "auto" => Ok(Subset::Automatic),
+            _ => Err(syn::Error::new(
+                ident.span(),
+                "subset should be latin or auto.",
+            )),
+        }
+    }
+
+    fn unicodes(&self) -> String {
+        match self {
+            Subset::Latin => LATIN_UNICODE_RANGES.to_string(),
+            Subset::Automatic => std::iter::once(LATIN_UNICODE_RANGES.to_string())
+                .chain(
+                    characters_used_in_templates()
+                        .into_iter()
+                        .map(|character| format!("U+{:04X}", character as u32)),
+                )
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
+}
+
+/// Subsets a font to the given characters and encodes it as WOFF2,
+/// returning the bytes of the new font.
+///
+/// Subsets are cached by the contents of the font and the characters to
+/// keep, since pyftsubset takes a second or two for a large font.
+pub fn subset_to_woff2(input_file_path: &Path, subset: Subset) -> Result<Vec<u8>, String> {
+    let font_bytes = fs::read(input_file_path)
+        .map_err(|error| format!("Error reading {}: {}", input_file_path.display(), error))?;
+    let unicodes = subset.unicodes();
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(CACHE_VERSION.as_bytes());
+    hasher.update(&font_bytes);
+    hasher.update(unicodes.as_bytes());
+    let cache_path = font_cache_dir().join(format!("{}.woff2", hasher.finalize().to_hex()));
+
+    if let Ok(bytes) = fs::read(&cache_path) {
+        return Ok(bytes);
+    }
+
+    log::info!(
+        "Couldn't read cached font subset {:?} so regenerating it.",
+        &cache_path
+    );
+
+    fs::create_dir_all(font_cache_dir()).expect("Error creating font cache dir.");
+    run_pyftsubset(input_file_path, &unicodes, &cache_path)?;
+
+    fs::read(&cache_path).map_err(|error| format!("Error reading font subset: {}", error))
+}
+
+fn run_pyftsubset(
+    input_file_path: &Path,
+    unicodes: &str,
+    output_path: &Path,
+) -> Result<(), String> {
+    let pyftsubset = path_to_python_binary("pyftsubset");
+
+    // Write to a temporary file first and then rename it, so that if two
+    // macros subset the same font at once, neither sees a partial file.
+    let temporary_path = output_path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+
+    let output = Command::new(&pyftsubset)
+        .arg(input_file_path)
+        .arg(format!("--unicodes={}", unicodes))
+        .arg("--flavor=woff2")
+        .arg("--layout-features=*")
+        .arg("--desubroutinize")
+        .arg(format!("--output-file={}", temporary_path.display()))
+        .output()
+        .map_err(|error| {
+            format!(
+                "Error running {}. Try running `./scripts setup` to install it. Original error message: {}",
+                pyftsubset.display(),
+                error
+            )
+        })?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "pyftsubset failed for {}: {}",
+            input_file_path.display(),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    fs::rename(&temporary_path, output_path)
+        .map_err(|error| format!("Error moving font subset into cache: {}", error))
+}
+
+// Looks through every html! macro in the workspace for string literals,
+// and collects the characters in them.
+fn characters_used_in_templates() -> BTreeSet<char> {
+    let mut characters = BTreeSet::new();
+
+    for path in rust_files_in_workspace() {
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        match TokenStream::from_str(&source) {
+            Ok(tokens) => collect_characters_in_html_macros(tokens, &mut characters),
+            Err(error) => log::info!("Error tokenizing {}: {}", path.display(), error),
+        }
+    }
+
+    characters
+}
+
+fn rust_files_in_workspace() -> Vec<PathBuf> {
+    WalkDir::new(workspace_root_dir())
+        .into_iter()
+        .filter_entry(|entry| {
+            let file_name = entry.file_name().to_string_lossy();
+            let is_ignored_dir =
+                file_name.starts_with('.') || file_name == "target" || file_name == "node_modules";
+            !is_ignored_dir
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .path()
+                .extension()
+                .is_some_and(|extension| extension == "rs")
+        })
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+fn collect_characters_in_html_macros(tokens: TokenStream, characters: &mut BTreeSet<char>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+
+    for (index, token) in tokens.iter().enumerate() {
+        let TokenTree::Group(group) = token else {
+            continue;
+        };
+
+        let is_html_macro = index >= 2
+            && matches!(
+                (&tokens[index - 2], &tokens[index - 1]),
+                (TokenTree::Ident(ident), TokenTree::Punct(punct))
+                    if ident == "html" && punct.as_char() == '!'
+            );
+
+        if is_html_macro {
+            collect_characters_in_literals(group.stream(), characters);
+        } else {
+            collect_characters_in_html_macros(group.stream(), characters);
+        }
+    }
+}
+
+fn collect_characters_in_literals(tokens: TokenStream, characters: &mut BTreeSet<char>) {
+    for token in tokens {
+        match token {
+            TokenTree::Literal(literal) => match syn::Lit::new(literal) {
+                syn::Lit::Str(string) => characters.extend(string.value().chars()),
+                syn::Lit::Char(character) => {
+                    characters.insert(character.value());
+                }
+                _ => {}
+            },
+            TokenTree::Group(group) => collect_characters_in_literals(group.stream(), characters),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_characters_inside_html_macros() {
+        let tokens = TokenStream::from_str(
+            r#"
+            fn page(title: &str) -> Markup {
+                let ignored = "xyz";
+                html! {
+                    h1 { (title) }
+                    p class="é" { "Café " (format!("{} →", 'ñ')) }
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let mut characters = BTreeSet::new();
+        collect_characters_in_html_macros(tokens, &mut characters);
+
+        for character in ['C', 'a', 'f', 'é', '→', 'ñ', ' '] {
+            assert!(characters.contains(&character), "missing {}", character);
+        }
+        for character in ['x', 'y', 'z'] {
+            assert!(!characters.contains(&character), "unexpected {}", character);
+        }
+    }
+
+    #[test]
+    fn automatic_subsets_keep_the_latin_characters() {
+        assert!(Subset::Automatic
+            .unicodes()
+            .starts_with(LATIN_UNICODE_RANGES));
+    }
+}
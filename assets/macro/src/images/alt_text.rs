@@ -0,0 +1,136 @@
+use super::captioning;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub enum Alt {
+    Literal(String),
+    // Look for the alt text in files next to the image.
+    FromSidecar,
+    // Like `FromSidecar`, but if there's no sidecar file, caption the
+    // image with the command from assets.toml and write one.
+    Automatic,
+    Decorative,
+}
+
+// Sidecar files written by the captioning command start with this line.
+// Once someone has checked the caption, they delete it, and we stop
+// warning about the caption.
+pub static MACHINE_GENERATED_MARKER: &str =
+    "# Machine-generated. Check this caption, then delete this line to confirm it.";
+
+// Alt text can come from three places, in order of preference:
+//
+// 1. The `alt` argument.
+// 2. A sidecar file next to the image, named like `santoka.jpg.alt.txt`.
+// 3. An `alt.toml` file in the image's folder, like this:
+//
+//    decorative = ["divider.png"]
+//
+//    [alt]
+//    "santoka.jpg" = "Taneda Santōka"
+//
+// Decorative images get an empty alt attribute, so screen readers skip them.
+pub fn resolve_alt(alt: Alt, absolute_path_to_image: &Path) -> Result<String, String> {
+    match alt {
+        Alt::Literal(alt) => Ok(alt),
+        Alt::Decorative => Ok("".to_string()),
+        Alt::FromSidecar => alt_from_sidecar_file(absolute_path_to_image)
+            .or_else(|| alt_from_folder_toml(absolute_path_to_image))
+            .ok_or_else(|| missing_alt_error_message(absolute_path_to_image)),
+        Alt::Automatic => match alt_from_sidecar_file(absolute_path_to_image) {
+            Some(alt) => Ok(alt),
+            None => captioning::caption(absolute_path_to_image),
+        },
+    }
+}
+
+pub fn sidecar_path(absolute_path_to_image: &Path) -> PathBuf {
+    let file_name = file_name(absolute_path_to_image);
+    absolute_path_to_image.with_file_name(format!("{}.alt.txt", file_name))
+}
+
+fn alt_from_sidecar_file(absolute_path_to_image: &Path) -> Option<String> {
+    let sidecar_path = sidecar_path(absolute_path_to_image);
+
+    let contents = fs::read_to_string(&sidecar_path).ok()?;
+    log::info!("Read alt text from {}.", sidecar_path.display());
+
+    let (alt, machine_generated) = match contents.strip_prefix(MACHINE_GENERATED_MARKER) {
+        Some(alt) => (alt.trim(), true),
+        None => (contents.trim(), false),
+    };
+
+    if alt.is_empty() {
+        return None;
+    }
+
+    if machine_generated {
+        warn_about_machine_generated_alt(&sidecar_path, alt);
+    }
+
+    Some(alt.to_string())
+}
+
+pub fn warn_about_machine_generated_alt(sidecar_path: &Path, alt: &str) {
+    log::warn!(
+        "Using machine-generated alt text \"{}\" from {}. Please check it, then delete the first line of the file to confirm it.",
+        alt,
+        sidecar_path.display()
+    );
+}
+
+fn alt_from_folder_toml(absolute_path_to_image: &Path) -> Option<String> {
+    let file_name = file_name(absolute_path_to_image);
+    let alt_toml_path = absolute_path_to_image.with_file_name("alt.toml");
+
+    let alt_toml = fs::read_to_string(&alt_toml_path).ok()?;
+    let alt_toml: toml::Table = alt_toml
+        .parse()
+        .unwrap_or_else(|error| panic!("Error parsing {}: {}", alt_toml_path.display(), error));
+
+    let is_decorative = alt_toml
+        .get("decorative")
+        .and_then(|decorative| decorative.as_array())
+        .map(|decorative| {
+            decorative.iter().any(|decorative_file_name| {
+                decorative_file_name.as_str() == Some(file_name.as_str())
+            })
+        })
+        .unwrap_or(false);
+
+    if is_decorative {
+        return Some("".to_string());
+    }
+
+    let alt = alt_toml
+        .get("alt")
+        .and_then(|alt| alt.as_table())
+        .and_then(|alt| alt.get(&file_name))
+        .and_then(|alt| alt.as_str())?;
+
+    Some(alt.trim().to_string()).filter(|alt| !alt.is_empty())
+}
+
+fn missing_alt_error_message(absolute_path_to_image: &Path) -> String {
+    let file_name = file_name(absolute_path_to_image);
+
+    format!(
+        r#"Couldn't find alt text for {path}. Images without alt text are inaccessible to people using screen readers, so please add it in one of these ways:
+
+- Pass it directly with `alt: "..."`.
+- Put it in a file next to the image called {file_name}.alt.txt.
+- Put it in an alt.toml file next to the image, under `[alt]` as `"{file_name}" = "..."`.
+
+If the image is purely decorative, pass `decorative: true` or list it in alt.toml as `decorative = ["{file_name}"]`."#,
+        path = absolute_path_to_image.display(),
+        file_name = file_name,
+    )
+}
+
+fn file_name(absolute_path_to_image: &Path) -> String {
+    absolute_path_to_image
+        .file_name()
+        .expect("Error getting image file name.")
+        .to_string_lossy()
+        .to_string()
+}
@@ -1,9 +1,20 @@
 use super::build_time_resized_image::*;
 use super::dynamic_image_extension::DynamicImageExtension;
-use assets_runtime::Placeholder;
-use image::{DynamicImage, GenericImageView};
+use super::encoding_options::EncodingOptions;
+use super::image_file::ImageFile;
+use super::image_metadata::ImageMetadata;
+use super::palette::extract_palette;
+use assets_runtime::{Color, Placeholder, Sizes};
+use base64::Engine;
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageFormat, RgbaImage};
 use std::path::{Path, PathBuf};
 
+// The widest viewport we expect to serve, for checking that an image's
+// resized copies cover its `sizes` at each of its densities.
+static WIDEST_VIEWPORT_PX: u32 = 1920;
+
+static PALETTE_SIZE: usize = 5;
+
 #[derive(Clone)]
 pub struct BuildTimeImage {
     pub absolute_path_to_original_image: PathBuf,
@@ -12,29 +23,51 @@ pub struct BuildTimeImage {
     pub placeholder: Placeholder,
     pub width: u32,
     pub height: u32,
+    pub sizes: Sizes,
+    pub palette: Vec<Color>,
     pub name_in_source_code: String,
     pub alt: String,
+    // The original's capture date and dimensions, for optional use.
+    #[allow(dead_code)]
+    pub metadata: ImageMetadata,
 }
 
 impl BuildTimeImage {
     pub fn new(
         path_to_images_dir: &Path,
-        absolute_path_to_original_image: PathBuf,
-        original_image: DynamicImage,
+        image_file: &ImageFile,
         placeholder_to_generate: PlaceholderToGenerate,
+        encoding_options: &EncodingOptions,
+        sizes: Sizes,
         alt: String,
-    ) -> Self {
+    ) -> Result<Self, String> {
+        let absolute_path_to_original_image = image_file.absolute_path_to_image.clone();
+        let original_image = &image_file.image;
         let path_starting_from_images_dir = absolute_path_to_original_image
             .strip_prefix(path_to_images_dir)
             .expect("Error stripping prefix from absolute path to original image.")
             .to_path_buf();
-        let (width, height) = original_image.dimensions();
+        let (width, height) = image_file.dimensions();
+        let encoding_options = EncodingOptions {
+            formats: Self::formats_to_generate(&encoding_options.formats, original_image),
+            ..encoding_options.clone()
+        };
         let resized_copies = Self::resized_copies(
             path_to_images_dir,
             &absolute_path_to_original_image,
-            &original_image,
+            original_image,
+            &image_file.hash,
+            &encoding_options,
         );
-        let placeholder = placeholder_to_generate.to_placeholder(&original_image);
+        let palette = extract_palette(original_image, PALETTE_SIZE);
+        let placeholder = placeholder_to_generate.to_placeholder(original_image, &palette);
+        Self::check_sizes_are_covered(
+            &absolute_path_to_original_image,
+            &sizes,
+            &encoding_options,
+            width,
+            &resized_copies,
+        )?;
 
         let file_stem = absolute_path_to_original_image
             .file_stem()
@@ -42,7 +75,7 @@ impl BuildTimeImage {
             .to_string_lossy()
             .to_string();
 
-        let name_in_source_code = file_stem.replace(
+        let name_in_source_code = file_stem.to_lowercase().replace(
             |character: char| {
                 let should_keep = character.is_alphanumeric() || character == '_';
                 !should_keep
@@ -50,57 +83,158 @@ impl BuildTimeImage {
             "_",
         );
 
-        BuildTimeImage {
+        // Identifiers can't start with a digit.
+        let name_in_source_code = if name_in_source_code.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("image_{}", name_in_source_code)
+        } else {
+            name_in_source_code
+        };
+
+        Ok(BuildTimeImage {
             path_starting_from_images_dir,
             absolute_path_to_original_image,
             resized_copies,
             placeholder,
             width,
             height,
+            sizes,
+            palette,
             name_in_source_code,
             alt,
+            metadata: image_file.metadata.clone(),
+        })
+    }
+
+    pub fn dominant_color(&self) -> Color {
+        self.palette.first().copied().unwrap_or(Color::new(0, 0, 0))
+    }
+
+    // Fails if the image might be displayed wider than our widest resized
+    // copy when we could have generated a wider one, since browsers would
+    // then have to upscale it. If the original or max_width is what limits
+    // the copies, there's nothing wider to generate, so we only warn.
+    fn check_sizes_are_covered(
+        absolute_path_to_original_image: &Path,
+        sizes: &Sizes,
+        encoding_options: &EncodingOptions,
+        original_width: u32,
+        resized_copies: &[BuildTimeResizedImage],
+    ) -> Result<(), String> {
+        let widest_copy = resized_copies
+            .iter()
+            .map(|resized_copy| resized_copy.width)
+            .max()
+            .unwrap_or(0);
+
+        let highest_density = encoding_options
+            .densities
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(1);
+
+        let widest_displayed = sizes
+            .lengths()
+            .map(|length| length.to_px(WIDEST_VIEWPORT_PX) * highest_density)
+            .max()
+            .unwrap_or(0);
+
+        if widest_copy >= widest_displayed {
+            return Ok(());
+        }
+
+        let widest_possible = encoding_options
+            .max_width
+            .map_or(original_width, |max_width| max_width.min(original_width));
+
+        if widest_copy < widest_displayed.min(widest_possible) {
+            return Err(format!(
+                "{} may be displayed up to {}px wide at {}x pixel density with sizes \"{}\", but the widest resized copy is {}px. Please add a wider width to `widths`, or use smaller sizes.",
+                absolute_path_to_original_image.display(),
+                widest_displayed,
+                highest_density,
+                sizes,
+                widest_copy
+            ));
         }
+
+        log::warn!(
+            "{} may be displayed up to {}px wide at {}x pixel density with sizes \"{}\", but it can only be resized to {}px. Consider using a larger original image or smaller sizes.",
+            absolute_path_to_original_image.display(),
+            widest_displayed,
+            highest_density,
+            sizes,
+            widest_copy
+        );
+
+        Ok(())
+    }
+
+    // If the original image has transparency, swap JPEG for PNG
+    // so that we don't lose the alpha channel.
+    fn formats_to_generate(
+        requested_formats: &[ImageFormat],
+        original_image: &DynamicImage,
+    ) -> Vec<ImageFormat> {
+        if !original_image.has_transparency() {
+            return requested_formats.to_vec();
+        }
+
+        let mut formats: Vec<ImageFormat> = vec![];
+        for format in requested_formats {
+            let format = match format {
+                ImageFormat::Jpeg => ImageFormat::Png,
+                other => *other,
+            };
+
+            if !formats.contains(&format) {
+                formats.push(format);
+            }
+        }
+
+        formats
     }
 
     fn resized_copies(
         path_to_images_dir: &Path,
         absolute_path_to_original_image: &Path,
         original_image: &DynamicImage,
+        original_image_hash: &blake3::Hash,
+        encoding_options: &EncodingOptions,
     ) -> Vec<BuildTimeResizedImage> {
-        let original_width = original_image.width();
+        let target_widths = encoding_options.target_widths(original_image.width());
+        let quality = encoding_options.quality;
 
-        Self::available_widths(original_width)
-            .into_iter()
-            .map(|target_width| {
+        encoding_options
+            .formats
+            .iter()
+            .flat_map(|format| {
+                target_widths
+                    .iter()
+                    .map(move |target_width| ResizeParameters::new(*target_width, *format, quality))
+            })
+            .map(|parameters| {
                 BuildTimeResizedImage::new(
-                    target_width,
+                    parameters,
                     path_to_images_dir,
                     absolute_path_to_original_image,
                     original_image,
+                    original_image_hash,
                 )
             })
             .collect()
     }
-
-    fn available_widths(image_width: u32) -> Vec<u32> {
-        Self::possible_widths()
-            .into_iter()
-            .filter(|possible_width| possible_width <= &image_width)
-            .collect()
-    }
-
-    fn possible_widths() -> Vec<u32> {
-        (100..=4000).step_by(100).collect()
-    }
 }
 
+#[derive(Clone, Copy)]
 pub enum PlaceholderToGenerate {
     Lqip,
     AutomaticallyDetectedColor,
+    ThumbHash,
 }
 
 impl PlaceholderToGenerate {
-    pub fn to_placeholder(&self, original_image: &DynamicImage) -> Placeholder {
+    pub fn to_placeholder(self, original_image: &DynamicImage, palette: &[Color]) -> Placeholder {
         match self {
             // Returns a string like "data:image/jpeg;base64,/9j/4AAQSk...".
             PlaceholderToGenerate::Lqip => {
@@ -108,23 +242,73 @@ impl PlaceholderToGenerate {
                 Placeholder::Lqip { data_uri }
             }
 
-            // Returns a string like "rgba(255, 255, 255, 1.0)".
+            // Returns a string like "rgba(255, 255, 255, 1.0)", using the
+            // most common color in the palette.
+            //
+            // Transparent pixels are left out of the palette, since otherwise
+            // they'd drag the color towards whatever color they happen to store.
             PlaceholderToGenerate::AutomaticallyDetectedColor => {
-                let [red, green, blue, alpha] = original_image
-                    .resize_exact(1, 1, image::imageops::Lanczos3)
-                    .get_pixel(0, 0)
-                    .0;
-
-                let css_string = format!(
-                    "rgba({red}, {green}, {blue}, {alpha})",
-                    red = red,
-                    green = green,
-                    blue = blue,
-                    alpha = alpha
-                );
+                let css_string = match palette.first() {
+                    Some(color) => format!(
+                        "rgba({red}, {green}, {blue}, 1.0)",
+                        red = color.red,
+                        green = color.green,
+                        blue = color.blue,
+                    ),
+                    None => "rgba(0, 0, 0, 0.0)".to_string(),
+                };
 
                 Placeholder::Color { css_string }
             }
+
+            // Returns a base64 encoded ThumbHash like "1QcSHQRnh493V4dIh4eXh1h4kJUI",
+            // which is only around 25 bytes, and a CSS background approximating it.
+            PlaceholderToGenerate::ThumbHash => {
+                // ThumbHash only accepts images up to 100x100.
+                let thumbnail = original_image.thumbnail(100, 100).to_rgba8();
+                let hash_bytes = thumbhash::rgba_to_thumb_hash(
+                    thumbnail.width() as usize,
+                    thumbnail.height() as usize,
+                    thumbnail.as_raw(),
+                );
+
+                let hash = base64::engine::general_purpose::STANDARD.encode(&hash_bytes);
+                let css_background = css_background_from_thumbhash(&hash_bytes);
+
+                Placeholder::ThumbHash {
+                    hash,
+                    css_background,
+                }
+            }
         }
     }
 }
+
+// Approximates a ThumbHash with a 3x3 grid of colors. Each row is a
+// horizontal gradient through its three colors, and the rows are stacked
+// as separate background layers. It's blurrier than decoding the hash
+// properly, but works without any JavaScript.
+fn css_background_from_thumbhash(hash_bytes: &[u8]) -> String {
+    let (width, height, rgba) =
+        thumbhash::thumb_hash_to_rgba(hash_bytes).expect("Error decoding ThumbHash.");
+
+    let grid = RgbaImage::from_raw(width as u32, height as u32, rgba)
+        .expect("Error reading decoded ThumbHash.");
+    let grid = DynamicImage::ImageRgba8(grid).resize_exact(3, 3, FilterType::Triangle);
+
+    let rows = (0..3).map(|y| {
+        let colors = (0..3)
+            .map(|x| {
+                let [red, green, blue, alpha] = grid.get_pixel(x, y).0;
+                let alpha = alpha as f32 / 255.0;
+                format!("rgba({red}, {green}, {blue}, {alpha:.2})")
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        let top = y * 50;
+        format!("linear-gradient(90deg, {colors}) 0 {top}% / 100% 34% no-repeat")
+    });
+
+    rows.collect::<Vec<String>>().join(", ")
+}
@@ -1,5 +1,5 @@
 use super::dynamic_image_extension::DynamicImageExtension;
-use image::DynamicImage;
+use image::{imageops::FilterType, DynamicImage, ImageFormat};
 use mime::Mime;
 use std::fs;
 use std::{
@@ -7,6 +7,10 @@ use std::{
     str::FromStr,
 };
 
+// Bump this to invalidate every cached resized image, e.g. after
+// changing how we encode them.
+static CACHE_VERSION: &str = "2";
+
 #[derive(Clone)]
 pub struct BuildTimeResizedImage {
     pub absolute_path: PathBuf,
@@ -17,19 +21,62 @@ pub struct BuildTimeResizedImage {
     pub bytes: Vec<u8>,
 }
 
+/// Everything apart from the original image that affects
+/// the bytes of a resized image.
+#[derive(Clone, Copy, Debug)]
+pub struct ResizeParameters {
+    pub width: u32,
+    pub format: ImageFormat,
+    pub quality: u8,
+    pub filter: FilterType,
+}
+
+impl ResizeParameters {
+    pub fn new(width: u32, format: ImageFormat, quality: Option<u8>) -> Self {
+        // These match the image crate's default quality for each encoder.
+        let quality = quality.unwrap_or(match format {
+            ImageFormat::Jpeg => 75,
+            _ => 80,
+        });
+
+        Self {
+            width,
+            format,
+            quality,
+            filter: FilterType::Lanczos3,
+        }
+    }
+
+    fn mime_type(&self) -> Mime {
+        mime_type_for_format(self.format)
+    }
+}
+
+pub fn mime_type_for_format(format: ImageFormat) -> Mime {
+    match format {
+        ImageFormat::Jpeg => mime::IMAGE_JPEG,
+        ImageFormat::Png => mime::IMAGE_PNG,
+        ImageFormat::WebP => Mime::from_str("image/webp").unwrap(),
+        ImageFormat::Avif => Mime::from_str("image/avif").unwrap(),
+        other => panic!("Unsupported resized image format: {:?}", other),
+    }
+}
+
 impl BuildTimeResizedImage {
     pub fn new(
-        width: u32,
+        parameters: ResizeParameters,
         path_to_images_dir: &Path,
         absolute_path_to_original_image: &Path,
         original_image: &DynamicImage,
+        original_image_hash: &blake3::Hash,
     ) -> Self {
         let path_starting_from_images_dir_without_width = absolute_path_to_original_image
             .strip_prefix(path_to_images_dir)
             .expect("Error stripping prefix from absolute path to original image.")
             .to_path_buf();
 
-        let mime_type = mime::IMAGE_JPEG;
+        let width = parameters.width;
+        let mime_type = parameters.mime_type();
 
         let path_starting_from_images_dir_with_width =
             Self::path_starting_from_images_dir_with_width(
@@ -43,7 +90,13 @@ impl BuildTimeResizedImage {
 
         let height = original_image.height_if_resized_to_width(width);
 
-        let bytes = Self::generate_bytes(width, &absolute_path_with_width, original_image);
+        let bytes = Self::generate_bytes(
+            &parameters,
+            &absolute_path_with_width,
+            original_image,
+            original_image_hash,
+        );
+        crate::stale_built_assets::register_output(&absolute_path_with_width);
 
         Self {
             absolute_path: absolute_path_with_width,
@@ -79,21 +132,68 @@ impl BuildTimeResizedImage {
         PathBuf::from_str(&new_file_name_string).unwrap()
     }
 
-    fn generate_bytes(width: u32, path: &Path, original_image: &DynamicImage) -> Vec<u8> {
-        fs::read(path).unwrap_or_else(|error| {
-            println!(
-                "Couldn't read resized image file {:?} so regenerating the resized image. Original error message: {}",
-                &path, error
+    // Resized images are cached by the contents of the original image and the
+    // resize parameters, rather than by file name. That way a changed original
+    // always gets regenerated, and identical originals at different paths only
+    // get resized once.
+    fn generate_bytes(
+        parameters: &ResizeParameters,
+        output_path: &Path,
+        original_image: &DynamicImage,
+        original_image_hash: &blake3::Hash,
+    ) -> Vec<u8> {
+        let cache_path = Self::cache_path(parameters, original_image_hash);
+
+        let bytes = fs::read(&cache_path).unwrap_or_else(|error| {
+            log::info!(
+                "Couldn't read cached resized image {:?} so regenerating it. Original error message: {}",
+                &cache_path, error
             );
 
             let bytes = original_image
-                .resize_to_width(width)
-                .to_bytes_with_format(image::ImageFormat::Jpeg);
+                .resize_to_width_with_filter(parameters.width, parameters.filter)
+                .to_bytes_with_format_and_quality(parameters.format, parameters.quality);
 
-            fs::create_dir_all(path.parent().unwrap()).expect("Error creating built images dir.");
-            fs::write(path, &bytes).expect("Error writing resized image to disk.");
+            Self::write_to_cache(&cache_path, &bytes);
 
             bytes
-        })
+        });
+
+        // Avoid touching the built file if it hasn't changed, so that
+        // file watchers don't see a change on every build.
+        let already_up_to_date = fs::read(output_path)
+            .map(|existing_bytes| existing_bytes == bytes)
+            .unwrap_or(false);
+
+        if !already_up_to_date {
+            fs::create_dir_all(output_path.parent().unwrap())
+                .expect("Error creating built images dir.");
+            fs::write(output_path, &bytes).expect("Error writing resized image to disk.");
+        }
+
+        bytes
+    }
+
+    fn cache_path(parameters: &ResizeParameters, original_image_hash: &blake3::Hash) -> PathBuf {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(CACHE_VERSION.as_bytes());
+        hasher.update(original_image_hash.as_bytes());
+        hasher.update(format!("{:?}", parameters).as_bytes());
+        let key = hasher.finalize().to_hex();
+
+        let extension = parameters.mime_type().subtype().as_str().to_string();
+
+        assets_runtime::paths::image_cache_dir().join(format!("{}.{}", key, extension))
+    }
+
+    fn write_to_cache(cache_path: &Path, bytes: &[u8]) {
+        let cache_dir = cache_path.parent().unwrap();
+        fs::create_dir_all(cache_dir).expect("Error creating image cache dir.");
+
+        // Write to a temporary file first and then rename it, so that if two
+        // macros resize the same image at once, neither sees a partial file.
+        let temporary_path = cache_dir.join(format!("{}.tmp", uuid::Uuid::new_v4()));
+        fs::write(&temporary_path, bytes).expect("Error writing resized image to cache.");
+        fs::rename(&temporary_path, cache_path).expect("Error moving resized image into cache.");
     }
 }
@@ -0,0 +1,91 @@
+use super::alt_text::{sidecar_path, warn_about_machine_generated_alt, MACHINE_GENERATED_MARKER};
+use crate::config::config;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Captions an image for `alt: automatic` using the command from assets.toml,
+// then writes the caption next to the image so that someone can check it.
+//
+// Captions are cached by the contents of the image, since running a
+// captioning model is slow and the same image always gets the same caption.
+pub fn caption(absolute_path_to_image: &Path) -> Result<String, String> {
+    let image_bytes = fs::read(absolute_path_to_image)
+        .map_err(|error| format!("Error reading image for captioning: {}", error))?;
+    let cache_path = cache_path(&blake3::hash(&image_bytes));
+
+    let caption = match fs::read_to_string(&cache_path) {
+        Ok(caption) => caption,
+        Err(error) => {
+            log::info!(
+                "Couldn't read cached caption {:?} so regenerating it. Original error message: {}",
+                &cache_path,
+                error
+            );
+
+            let caption = run_captioning_command(absolute_path_to_image)?;
+            fs::create_dir_all(cache_path.parent().unwrap())
+                .expect("Error creating caption cache dir.");
+            fs::write(&cache_path, &caption).expect("Error writing caption to cache.");
+            caption
+        }
+    };
+
+    let sidecar_path = sidecar_path(absolute_path_to_image);
+    fs::write(
+        &sidecar_path,
+        format!("{}\n{}\n", MACHINE_GENERATED_MARKER, caption),
+    )
+    .expect("Error writing caption sidecar file.");
+    warn_about_machine_generated_alt(&sidecar_path, &caption);
+
+    Ok(caption)
+}
+
+fn run_captioning_command(absolute_path_to_image: &Path) -> Result<String, String> {
+    let captioning = config().captioning.as_ref().ok_or_else(|| {
+        format!(
+            "{} uses `alt: automatic`, but there's no captioning command. Please add one to assets.toml, like this:
+
+[captioning]
+command = [\"python3\", \"scripts/caption_image.py\"]",
+            absolute_path_to_image.display()
+        )
+    })?;
+
+    let (program, arguments) = captioning
+        .command
+        .split_first()
+        .expect("The captioning command shouldn't be empty.");
+
+    log::info!("Captioning {}.", absolute_path_to_image.display());
+
+    let output = Command::new(program)
+        .args(arguments)
+        .arg(absolute_path_to_image)
+        .current_dir(assets_runtime::paths::workspace_root_dir())
+        .output()
+        .map_err(|error| format!("Error running captioning command {}: {}", program, error))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Captioning command failed for {}: {}",
+            absolute_path_to_image.display(),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let caption = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if caption.is_empty() {
+        return Err(format!(
+            "Captioning command didn't print a caption for {}.",
+            absolute_path_to_image.display()
+        ));
+    }
+
+    Ok(caption)
+}
+
+fn cache_path(image_hash: &blake3::Hash) -> PathBuf {
+    assets_runtime::paths::caption_cache_dir().join(format!("{}.txt", image_hash.to_hex()))
+}
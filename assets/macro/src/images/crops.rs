@@ -0,0 +1,236 @@
+use crate::parse_macro_arguments::*;
+use image::{DynamicImage, GenericImageView};
+use syn::{
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    Ident, Lit, LitStr, Result as SynResult, Token,
+};
+
+/// A named aspect ratio variant of an image, like `card: "16:9"`.
+#[derive(Clone, Debug)]
+pub struct Crop {
+    pub name: String,
+    pub aspect_ratio: (u32, u32),
+    pub focal_point: (f32, f32),
+}
+
+impl Crop {
+    // The largest rectangle with the crop's aspect ratio that fits in the
+    // image, centered on the focal point as closely as the edges allow.
+    // Returns (x, y, width, height).
+    pub fn rectangle(&self, image: &DynamicImage) -> (u32, u32, u32, u32) {
+        let (image_width, image_height) = image.dimensions();
+        let (ratio_width, ratio_height) = self.aspect_ratio;
+
+        let (width, height) = if image_width as u64 * ratio_height as u64
+            > image_height as u64 * ratio_width as u64
+        {
+            // The image is wider than the crop, so use its full height.
+            let width = image_height as u64 * ratio_width as u64 / ratio_height as u64;
+            (width as u32, image_height)
+        } else {
+            let height = image_width as u64 * ratio_height as u64 / ratio_width as u64;
+            (image_width, height as u32)
+        };
+
+        let (focal_x, focal_y) = self.focal_point;
+        let centered_x = focal_x * image_width as f32 - width as f32 / 2.0;
+        let centered_y = focal_y * image_height as f32 - height as f32 / 2.0;
+
+        let x = centered_x.clamp(0.0, (image_width - width) as f32) as u32;
+        let y = centered_y.clamp(0.0, (image_height - height) as f32) as u32;
+
+        (x, y, width, height)
+    }
+}
+
+/// Where the subject of an image is, as fractions of its width and height
+/// from the top left corner.
+#[derive(Clone, Copy)]
+pub enum FocalPoint {
+    Fixed { x: f32, y: f32 },
+    // Finds the busiest part of the image.
+    Automatic,
+}
+
+impl Default for FocalPoint {
+    fn default() -> Self {
+        FocalPoint::Fixed { x: 0.5, y: 0.5 }
+    }
+}
+
+impl FocalPoint {
+    pub fn resolve(&self, image: &DynamicImage) -> (f32, f32) {
+        match self {
+            FocalPoint::Fixed { x, y } => (*x, *y),
+            FocalPoint::Automatic => detect_focal_point(image),
+        }
+    }
+}
+
+// Photos usually have a detailed subject in front of a smoother background,
+// like an out of focus backdrop or the sky. So we run a simple edge detector
+// over a small copy of the image and use the center of mass of the edges.
+fn detect_focal_point(image: &DynamicImage) -> (f32, f32) {
+    let grayscale = image.thumbnail(64, 64).to_luma8();
+    let (width, height) = grayscale.dimensions();
+
+    let brightness = |x: u32, y: u32| grayscale.get_pixel(x, y).0[0] as f32;
+
+    let mut total_weight = 0.0;
+    let mut weighted_x = 0.0;
+    let mut weighted_y = 0.0;
+
+    for y in 1..height.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            let horizontal_edge = brightness(x + 1, y) - brightness(x - 1, y);
+            let vertical_edge = brightness(x, y + 1) - brightness(x, y - 1);
+            let edge_strength = (horizontal_edge.powi(2) + vertical_edge.powi(2)).sqrt();
+
+            total_weight += edge_strength;
+            weighted_x += edge_strength * x as f32;
+            weighted_y += edge_strength * y as f32;
+        }
+    }
+
+    // A completely flat image has no edges, so just use the center.
+    if total_weight == 0.0 {
+        return (0.5, 0.5);
+    }
+
+    (
+        weighted_x / total_weight / width as f32,
+        weighted_y / total_weight / height as f32,
+    )
+}
+
+/// parse_crops(input) will parse the following input:
+/// ```text
+///    crops: { card: "16:9", thumb: "1:1" },
+/// ```
+/// The crops don't have a focal point yet, since that
+/// comes from a separate argument. Each crop becomes a field
+/// next to `original`, so the names have to be unique.
+pub fn parse_crops(input: &ParseStream) -> SynResult<Vec<(Ident, (u32, u32))>> {
+    // This argument is optional, so we default to no crops.
+    if parse_argument_name_and_colon("crops", input).is_none() {
+        return Ok(vec![]);
+    }
+
+    let group: proc_macro2::Group = input.parse()?;
+    if group.delimiter() != proc_macro2::Delimiter::Brace {
+        return Err(syn::Error::new(
+            group.span(),
+            "crops should look like { card: \"16:9\", thumb: \"1:1\" }.",
+        ));
+    }
+
+    let mut crops: Vec<(Ident, (u32, u32))> = vec![];
+    for crop in Punctuated::<CropArgument, Token![,]>::parse_terminated.parse2(group.stream())? {
+        let name_is_taken =
+            crop.name == "original" || crops.iter().any(|(name, _)| *name == crop.name);
+        if name_is_taken {
+            return Err(syn::Error::new(
+                crop.name.span(),
+                format!(
+                    "There's already a field called {}. Please give this crop a different name.",
+                    crop.name
+                ),
+            ));
+        }
+
+        crops.push((crop.name, crop.aspect_ratio));
+    }
+
+    // Parse the optional comma after the argument. Note how
+    // we ignore the error here since the comma is optional.
+    let _: Result<Token![,], _> = input.parse();
+
+    Ok(crops)
+}
+
+struct CropArgument {
+    name: Ident,
+    aspect_ratio: (u32, u32),
+}
+
+impl Parse for CropArgument {
+    fn parse(input: ParseStream) -> SynResult<Self> {
+        let name: Ident = input.parse()?;
+        let _: Token![:] = input.parse()?;
+        let aspect_ratio_literal: LitStr = input.parse()?;
+
+        let aspect_ratio = aspect_ratio_literal
+            .value()
+            .split_once(':')
+            .and_then(|(width, height)| {
+                Some((width.trim().parse().ok()?, height.trim().parse().ok()?))
+            })
+            .filter(|(width, height)| *width > 0 && *height > 0)
+            .ok_or_else(|| {
+                syn::Error::new(
+                    aspect_ratio_literal.span(),
+                    "Aspect ratios should look like \"16:9\".",
+                )
+            })?;
+
+        Ok(CropArgument { name, aspect_ratio })
+    }
+}
+
+/// parse_focal_point(input) will parse any of the following inputs:
+/// ```text
+///    focal_point: (0.5, 0.3),
+///    focal_point: (1, 0),
+///    focal_point: automatic,
+/// ```
+pub fn parse_focal_point(input: &ParseStream) -> SynResult<FocalPoint> {
+    // This argument is optional, so we default to the center.
+    if parse_argument_name_and_colon("focal_point", input).is_none() {
+        return Ok(FocalPoint::default());
+    }
+
+    let focal_point = if input.peek(Ident) {
+        let ident: Ident = input.parse()?;
+        if ident != "automatic" {
+            return Err(syn::Error::new(
+                ident.span(),
+                "focal_point should be automatic or look like (0.5, 0.3).",
+            ));
+        }
+
+        FocalPoint::Automatic
+    } else {
+        let group: proc_macro2::Group = input.parse()?;
+        let coordinates = Punctuated::<Lit, Token![,]>::parse_terminated
+            .parse2(group.stream())?
+            .into_iter()
+            .map(|coordinate| match coordinate {
+                Lit::Float(float) => float.base10_parse::<f32>(),
+                Lit::Int(int) => int.base10_parse::<f32>(),
+                _ => Err(syn::Error::new(
+                    coordinate.span(),
+                    "focal_point coordinates should be numbers, like (0.5, 0.3).",
+                )),
+            })
+            .collect::<SynResult<Vec<f32>>>()?;
+
+        match coordinates[..] {
+            [x, y] if (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y) => {
+                FocalPoint::Fixed { x, y }
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    group.span(),
+                    "focal_point should be two numbers between 0 and 1, like (0.5, 0.3).",
+                ))
+            }
+        }
+    };
+
+    // Parse the optional comma after the argument. Note how
+    // we ignore the error here since the comma is optional.
+    let _: Result<Token![,], _> = input.parse();
+
+    Ok(focal_point)
+}
@@ -1,18 +1,26 @@
 use base64::Engine;
-use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageFormat};
+use image::codecs::avif::AvifEncoder;
+use image::codecs::webp::{WebPEncoder, WebPQuality};
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageFormat, ImageOutputFormat};
 use std::io::Cursor;
 
 pub trait DynamicImageExtension {
     fn resize_to_width(&self, new_width: u32) -> Self;
+    fn resize_to_width_with_filter(&self, new_width: u32, filter: FilterType) -> Self;
     fn to_data_uri(&self) -> String;
-    fn to_bytes_with_format(&self, format: ImageFormat) -> Vec<u8>;
+    fn to_bytes_with_format_and_quality(&self, format: ImageFormat, quality: u8) -> Vec<u8>;
     fn height_if_resized_to_width(&self, new_width: u32) -> u32;
+    fn has_transparency(&self) -> bool;
 }
 
 impl DynamicImageExtension for DynamicImage {
     fn resize_to_width(&self, new_width: u32) -> Self {
+        self.resize_to_width_with_filter(new_width, FilterType::Lanczos3)
+    }
+
+    fn resize_to_width_with_filter(&self, new_width: u32, filter: FilterType) -> Self {
         let new_height = self.height_if_resized_to_width(new_width);
-        self.resize(new_width, new_height, FilterType::Lanczos3)
+        self.resize(new_width, new_height, filter)
     }
 
     fn height_if_resized_to_width(&self, new_width: u32) -> u32 {
@@ -29,13 +37,18 @@ impl DynamicImageExtension for DynamicImage {
     }
 
     fn to_data_uri(&self) -> String {
+        // JPEG is smaller, but can't represent transparency.
+        let (format, mime_type) = if self.has_transparency() {
+            (ImageFormat::Png, mime::IMAGE_PNG)
+        } else {
+            (ImageFormat::Jpeg, mime::IMAGE_JPEG)
+        };
+
         let mut bytes: Cursor<Vec<u8>> = Cursor::new(Vec::new());
-        self.write_to(&mut bytes, ImageFormat::Jpeg)
+        self.write_to(&mut bytes, format)
             .expect("Error encoding low quality image placeholder.");
         let base64_encoded = base64::engine::general_purpose::STANDARD.encode(bytes.into_inner());
 
-        let mime_type = mime::IMAGE_JPEG;
-
         format!(
             "data:{mime_type};base64,{base64}",
             mime_type = mime_type,
@@ -43,10 +56,29 @@ impl DynamicImageExtension for DynamicImage {
         )
     }
 
-    fn to_bytes_with_format(&self, format: ImageFormat) -> Vec<u8> {
+    fn to_bytes_with_format_and_quality(&self, format: ImageFormat, quality: u8) -> Vec<u8> {
         let mut bytes: Cursor<Vec<u8>> = Cursor::new(Vec::new());
-        self.write_to(&mut bytes, format)
-            .expect("Error encoding image.");
+
+        // The WebP and AVIF encoders only accept 8-bit RGB(A) pixels.
+        let encoding_result = match format {
+            ImageFormat::Jpeg => self.write_to(&mut bytes, ImageOutputFormat::Jpeg(quality)),
+            ImageFormat::WebP => DynamicImage::ImageRgba8(self.to_rgba8()).write_with_encoder(
+                WebPEncoder::new_with_quality(&mut bytes, WebPQuality::lossy(quality)),
+            ),
+            ImageFormat::Avif => DynamicImage::ImageRgba8(self.to_rgba8())
+                .write_with_encoder(AvifEncoder::new_with_speed_quality(&mut bytes, 4, quality)),
+            other => self.write_to(&mut bytes, other),
+        };
+
+        encoding_result.expect("Error encoding image.");
         bytes.into_inner()
     }
+
+    fn has_transparency(&self) -> bool {
+        if !self.color().has_alpha() {
+            return false;
+        }
+
+        self.to_rgba8().pixels().any(|pixel| pixel.0[3] < u8::MAX)
+    }
 }
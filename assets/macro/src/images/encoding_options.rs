@@ -0,0 +1,197 @@
+use super::include_image_input::parse_formats;
+use crate::config::config;
+use crate::parse_macro_arguments::*;
+use image::ImageFormat;
+use syn::{parse::ParseStream, Result as SynResult};
+
+// Without any configuration we generate every width from 100 to 4000 in
+// steps of 100, which covers everything but can be dozens of files per
+// image. Projects should usually set smaller defaults in assets.toml.
+fn default_widths() -> Vec<u32> {
+    (100..=4000).step_by(100).collect()
+}
+
+/// Which resized copies of an image to generate and how to encode them.
+#[derive(Clone)]
+pub struct EncodingOptions {
+    pub formats: Vec<ImageFormat>,
+    // Widths in CSS pixels. Each one is multiplied by each density.
+    pub widths: Vec<u32>,
+    pub densities: Vec<u32>,
+    // No copy is wider than this, or than the original image.
+    pub max_width: Option<u32>,
+    // Uses each encoder's default quality if not set.
+    pub quality: Option<u8>,
+}
+
+impl EncodingOptions {
+    // The widths of the resized copies to generate, in image pixels.
+    pub fn target_widths(&self, original_width: u32) -> Vec<u32> {
+        let max_width = self
+            .max_width
+            .map_or(original_width, |max_width| max_width.min(original_width));
+
+        let requested_widths = self
+            .requested_widths()
+            .expect("Widths times densities should fit in a u32, which we check when parsing.");
+
+        let mut target_widths: Vec<u32> = requested_widths
+            .iter()
+            .copied()
+            .filter(|target_width| *target_width <= max_width)
+            .collect();
+
+        // If the image is smaller than some of the widths, also use it at
+        // its own size, capped at max_width, so that we serve the sharpest
+        // copy we can to screens that want one of the bigger widths.
+        if requested_widths.iter().any(|width| *width > max_width) {
+            target_widths.push(max_width);
+        }
+
+        target_widths.sort_unstable();
+        target_widths.dedup();
+        target_widths
+    }
+
+    // Each width multiplied by each density, or the first width and
+    // density whose product doesn't fit in a u32.
+    fn requested_widths(&self) -> Result<Vec<u32>, (u32, u32)> {
+        self.widths
+            .iter()
+            .flat_map(|width| self.densities.iter().map(move |density| (*width, *density)))
+            .map(|(width, density)| width.checked_mul(density).ok_or((width, density)))
+            .collect()
+    }
+}
+
+/// Parses the `formats`, `widths`, `max_width`, `densities`, and `quality`
+/// arguments, in that order. Each one is optional, and falls back to the
+/// `[images]` section of assets.toml, and then to a built-in default.
+pub fn parse_encoding_options(input: &ParseStream) -> SynResult<EncodingOptions> {
+    let images_config = &config().images;
+
+    let formats = parse_formats(input)?;
+
+    let widths_span = input.span();
+    let widths_argument = parse_named_u32_list_argument("widths", input)?;
+    let widths_span = widths_argument.is_some().then_some(widths_span);
+    let widths = widths_argument
+        .or_else(|| images_config.widths.clone())
+        .unwrap_or_else(default_widths);
+
+    let max_width = match parse_named_int_argument("max_width", input)? {
+        Some(max_width) => Some(parse_positive_u32("max_width", &max_width)?),
+        None => images_config.max_width,
+    };
+
+    let densities_span = input.span();
+    let densities_argument = parse_named_u32_list_argument("densities", input)?;
+    let densities_span = densities_argument.is_some().then_some(densities_span);
+    let densities = densities_argument
+        .or_else(|| images_config.densities.clone())
+        .unwrap_or_else(|| vec![1]);
+
+    let quality = match parse_named_int_argument("quality", input)? {
+        Some(quality) => match quality.base10_parse::<u8>() {
+            Ok(quality_value @ 1..=100) => Some(quality_value),
+            _ => {
+                return Err(syn::Error::new(
+                    quality.span(),
+                    "quality should be between 1 and 100.",
+                ))
+            }
+        },
+        None => images_config.quality,
+    };
+
+    if widths.is_empty() || densities.is_empty() {
+        return Err(syn::Error::new(
+            input.span(),
+            "widths and densities can't be empty.",
+        ));
+    }
+
+    let encoding_options = EncodingOptions {
+        formats,
+        widths,
+        densities,
+        max_width,
+        quality,
+    };
+
+    // Point at whichever of the arguments made the widths too big,
+    // or at the whole macro if they both came from assets.toml.
+    if let Err((width, density)) = encoding_options.requested_widths() {
+        return Err(syn::Error::new(
+            densities_span
+                .or(widths_span)
+                .unwrap_or_else(proc_macro2::Span::call_site),
+            format!(
+                "{}px at {}x pixel density is wider than {}px, the widest image we can generate.",
+                width,
+                density,
+                u32::MAX
+            ),
+        ));
+    }
+
+    Ok(encoding_options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+    use syn::parse::Parser;
+
+    fn parse(arguments: proc_macro2::TokenStream) -> SynResult<EncodingOptions> {
+        (|input: ParseStream| parse_encoding_options(&input)).parse2(arguments)
+    }
+
+    fn error_message(arguments: proc_macro2::TokenStream) -> String {
+        match parse(arguments) {
+            Ok(_) => panic!("Expected an error."),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_every_option() {
+        let encoding_options = parse(quote! {
+            widths: [400, 800],
+            max_width: 1200,
+            densities: [1, 2],
+            quality: 80,
+        })
+        .unwrap();
+
+        assert_eq!(encoding_options.widths, vec![400, 800]);
+        assert_eq!(encoding_options.max_width, Some(1200));
+        assert_eq!(encoding_options.densities, vec![1, 2]);
+        assert_eq!(encoding_options.quality, Some(80));
+        assert_eq!(encoding_options.target_widths(2000), vec![400, 800, 1200]);
+    }
+
+    #[test]
+    fn rejects_malformed_lists() {
+        assert!(error_message(quote! { widths: 640 }).contains("list of numbers"));
+        assert!(error_message(quote! { densities: [a] }).contains("list of numbers"));
+    }
+
+    #[test]
+    fn rejects_numbers_that_are_zero_or_too_big() {
+        assert!(error_message(quote! { widths: [0, 800] }).contains("between 1 and"));
+        assert!(error_message(quote! { max_width: 0 }).contains("between 1 and"));
+        assert!(error_message(quote! { max_width: 4294967296 }).contains("between 1 and"));
+        assert!(error_message(quote! { quality: 356 }).contains("between 1 and 100"));
+    }
+
+    #[test]
+    fn rejects_widths_times_densities_that_overflow() {
+        let message = error_message(quote! {
+            widths: [4000000000],
+            densities: [2],
+        });
+        assert!(message.contains("4000000000px at 2x"));
+    }
+}
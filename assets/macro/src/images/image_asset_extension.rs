@@ -1,6 +1,7 @@
 use super::build_time_image::BuildTimeImage;
 use super::build_time_resized_image::BuildTimeResizedImage;
-use assets_runtime::ImageAsset;
+use assets_runtime::{ImageAsset, ImageSource};
+use mime::Mime;
 
 pub trait ImageAssetExtension {
     fn from_build_time_image(build_time_image: &BuildTimeImage) -> Self;
@@ -8,24 +9,79 @@ pub trait ImageAssetExtension {
 
 impl ImageAssetExtension for ImageAsset {
     fn from_build_time_image(build_time_image: &BuildTimeImage) -> Self {
+        let copies_by_mime_type = group_by_mime_type(&build_time_image.resized_copies);
+
+        let fallback_mime_type = fallback_mime_type(&copies_by_mime_type);
+        let fallback_copies = copies_by_mime_type
+            .iter()
+            .find(|(mime_type, _)| mime_type == &fallback_mime_type)
+            .map(|(_, copies)| copies.clone())
+            .expect("Received a built image with no resized copies.");
+
+        let sources = copies_by_mime_type
+            .iter()
+            .filter(|(mime_type, _)| mime_type != &fallback_mime_type)
+            .map(|(mime_type, copies)| ImageSource {
+                mime_type: mime_type.to_string(),
+                srcset: generate_srcset(copies),
+            })
+            .collect();
+
         ImageAsset {
             alt: build_time_image.alt.clone(),
             placeholder: build_time_image.placeholder.clone(),
             width: build_time_image.width,
             height: build_time_image.height,
-            srcset: generate_srcset(&build_time_image.resized_copies),
-            src: generate_src(build_time_image),
+            dominant_color: build_time_image.dominant_color(),
+            palette: build_time_image.palette.clone(),
+            sizes: build_time_image.sizes.clone(),
+            sources,
+            srcset: generate_srcset(&fallback_copies),
+            src: generate_src(&fallback_copies),
         }
     }
 }
 
-fn generate_src(built_image: &BuildTimeImage) -> String {
+// Groups resized copies by format, keeping the order the formats were requested in.
+fn group_by_mime_type(
+    resized_copies: &[BuildTimeResizedImage],
+) -> Vec<(Mime, Vec<&BuildTimeResizedImage>)> {
+    let mut groups: Vec<(Mime, Vec<&BuildTimeResizedImage>)> = vec![];
+
+    for resized_copy in resized_copies {
+        match groups
+            .iter_mut()
+            .find(|(mime_type, _)| mime_type == &resized_copy.mime_type)
+        {
+            Some((_, copies)) => copies.push(resized_copy),
+            None => groups.push((resized_copy.mime_type.clone(), vec![resized_copy])),
+        }
+    }
+
+    groups
+}
+
+// Every browser supports JPEG and PNG, so prefer them as the fallback.
+// Images with transparency get PNG instead of JPEG.
+fn fallback_mime_type(copies_by_mime_type: &[(Mime, Vec<&BuildTimeResizedImage>)]) -> Mime {
+    copies_by_mime_type
+        .iter()
+        .map(|(mime_type, _)| mime_type.clone())
+        .find(|mime_type| mime_type == &mime::IMAGE_JPEG || mime_type == &mime::IMAGE_PNG)
+        .or_else(|| {
+            copies_by_mime_type
+                .last()
+                .map(|(mime_type, _)| mime_type.clone())
+        })
+        .expect("Received a built image with no resized copies.")
+}
+
+fn generate_src(resized_copies: &[&BuildTimeResizedImage]) -> String {
     // If their browser doesn't have support for the srcset attribute,
     // it's probably an old mobile browser. If that's the case, they
     // also probably don't have a lot of bandwidth so go with the smallest
     // image possible.
-    let narrowest = built_image
-        .resized_copies
+    let narrowest = resized_copies
         .iter()
         .min_by_key(|resized_copy| resized_copy.width)
         .expect("Received a built image with no resized copies.");
@@ -35,7 +91,7 @@ fn generate_src(built_image: &BuildTimeImage) -> String {
         .to_string()
 }
 
-fn generate_srcset(resized_copies: &[BuildTimeResizedImage]) -> String {
+fn generate_srcset(resized_copies: &[&BuildTimeResizedImage]) -> String {
     resized_copies
         .iter()
         .map(|resized_copy| {
@@ -0,0 +1,73 @@
+use super::crops::Crop;
+use super::image_metadata::ImageMetadata;
+use image::{DynamicImage, GenericImageView};
+use std::path::{Path, PathBuf};
+
+/// An image to generate resized copies of, along with the path we name
+/// those copies after and a hash we cache them by.
+pub struct ImageFile {
+    pub absolute_path_to_image: PathBuf,
+    pub image: DynamicImage,
+    pub hash: blake3::Hash,
+    pub metadata: ImageMetadata,
+}
+
+impl ImageFile {
+    pub fn open(path: &Path) -> Option<ImageFile> {
+        match image::open(path) {
+            Ok(dynamic_image) => {
+                let bytes = std::fs::read(path).expect("Error reading image for hashing.");
+                let metadata = ImageMetadata::read(&bytes, &dynamic_image);
+
+                Some(ImageFile {
+                    absolute_path_to_image: path.to_path_buf(),
+                    image: metadata.apply_orientation(dynamic_image),
+                    hash: blake3::hash(&bytes),
+                    metadata,
+                })
+            }
+            Err(error) => {
+                log::info!("Error opening file as image {:?}: {:?}", path, error);
+                None
+            }
+        }
+    }
+
+    // Crops go in a folder named after the original, like
+    // `santoka.jpg.crops/card.jpg`, so their resized copies can't clash with
+    // the original's or another image's. They're hashed with the crop
+    // rectangle, so changing the crop regenerates them.
+    pub fn crop(&self, crop: &Crop) -> ImageFile {
+        let (x, y, width, height) = crop.rectangle(&self.image);
+
+        let file_name = self
+            .absolute_path_to_image
+            .file_name()
+            .expect("Error parsing file name.")
+            .to_string_lossy();
+        let extension = self
+            .absolute_path_to_image
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let absolute_path_to_image = self
+            .absolute_path_to_image
+            .with_file_name(format!("{}.crops", file_name))
+            .join(format!("{}.{}", crop.name, extension));
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(self.hash.as_bytes());
+        hasher.update(format!("{:?}", (x, y, width, height)).as_bytes());
+
+        ImageFile {
+            absolute_path_to_image,
+            image: self.image.crop_imm(x, y, width, height),
+            hash: hasher.finalize(),
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.image.dimensions()
+    }
+}
@@ -0,0 +1,70 @@
+use image::DynamicImage;
+
+/// What we keep from an original image's metadata. Resized copies are
+/// re-encoded from the decoded pixels, so everything else, like GPS
+/// location and camera serial numbers, never makes it into the build.
+#[derive(Clone, Debug, Default)]
+pub struct ImageMetadata {
+    // The EXIF orientation, from 1 to 8. 1 means the pixels are
+    // already stored the right way up.
+    pub orientation: u32,
+    // When the photo was taken, like "2023-06-01 14:33:01".
+    // Not used yet, but handy for things like photo galleries.
+    #[allow(dead_code)]
+    pub captured_at: Option<String>,
+    // The dimensions of the stored pixels, before applying the orientation.
+    #[allow(dead_code)]
+    pub original_width: u32,
+    #[allow(dead_code)]
+    pub original_height: u32,
+}
+
+impl ImageMetadata {
+    pub fn read(bytes: &[u8], image: &DynamicImage) -> ImageMetadata {
+        let mut metadata = ImageMetadata {
+            orientation: 1,
+            captured_at: None,
+            original_width: image.width(),
+            original_height: image.height(),
+        };
+
+        // Plenty of images have no EXIF data at all, like most PNGs.
+        let exif = match exif::Reader::new().read_from_container(&mut std::io::Cursor::new(bytes)) {
+            Ok(exif) => exif,
+            Err(error) => {
+                log::info!("No EXIF data found. Original error message: {}", error);
+                return metadata;
+            }
+        };
+
+        if let Some(orientation) = exif
+            .get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+            .and_then(|field| field.value.get_uint(0))
+            .filter(|orientation| (1..=8).contains(orientation))
+        {
+            metadata.orientation = orientation;
+        }
+
+        metadata.captured_at = exif
+            .get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY)
+            .map(|field| field.display_value().to_string());
+
+        metadata
+    }
+
+    // Phones usually store photos the way the sensor saw them and set the
+    // orientation tag instead of rotating the pixels. Browsers respect the
+    // tag, but we strip it when re-encoding, so we rotate the pixels here.
+    pub fn apply_orientation(&self, image: DynamicImage) -> DynamicImage {
+        match self.orientation {
+            2 => image.fliph(),
+            3 => image.rotate180(),
+            4 => image.flipv(),
+            5 => image.rotate90().fliph(),
+            6 => image.rotate90(),
+            7 => image.rotate270().fliph(),
+            8 => image.rotate270(),
+            _ => image,
+        }
+    }
+}
@@ -1,17 +1,29 @@
 use crate::parse_macro_arguments::*;
+use assets_runtime::Sizes;
+use image::ImageFormat;
+use proc_macro2::Span;
 use std::path::PathBuf;
 use syn::{
     parse::{Parse, ParseStream},
     Ident, LitStr, Result as SynResult, Token,
 };
 
+use super::alt_text::{resolve_alt, Alt};
 use super::build_time_image::PlaceholderToGenerate;
+use super::crops::{parse_crops, parse_focal_point, FocalPoint};
+use super::encoding_options::{parse_encoding_options, EncodingOptions};
 
 /// This struct represents the input to the `include_image!` macro.
 pub struct IncludeImageInput {
     pub absolute_path_to_image: PathBuf,
     pub placeholder_to_generate: PlaceholderToGenerate,
-    pub alt: Alt,
+    pub encoding_options: EncodingOptions,
+    pub sizes: Sizes,
+    // Only set when there are crops, since then we define a struct.
+    pub struct_name: Option<Ident>,
+    pub crops: Vec<(Ident, (u32, u32))>,
+    pub focal_point: FocalPoint,
+    pub alt: String,
     pub debug: bool,
 }
 
@@ -24,16 +36,42 @@ include_image!(
     alt: \"Taneda Santōka\",
 );
 
-There are also some optional arguments. `placeholder` can be `lqip` or `automatic_color`. `lqip` generates a low resolution version of the image as a base64 string, suitable for embedding directly into html. `automatic_color` computes the dominant color of the image. `debug` can be `true` or `false`.
+The alt text can also go in a file next to the image called `santoka.jpg.alt.txt`, or in an `alt.toml` file in the image's folder. Decorative images should pass `decorative: true` instead. Every other image needs alt text.
+
+With `alt: automatic`, images without a sidecar file are captioned by the command in the `[captioning]` section of assets.toml, and the caption is written to a sidecar file for review.
+
+There are also some optional arguments. `placeholder` can be `lqip`, `automatic_color` or `thumbhash`. `lqip` generates a low resolution version of the image as a base64 string, suitable for embedding directly into html. `automatic_color` computes the dominant color of the image. `thumbhash` generates a tiny ThumbHash and a CSS gradient approximating it, which is much smaller than `lqip`. `formats` lists the formats to encode the image in, in order of preference, and can include `avif`, `webp`, `jpeg` and `png`. JPEG is always included as a fallback, or PNG if the image has transparency. `widths` lists widths in CSS pixels to generate resized copies at, and each one is multiplied by each of the `densities`. `max_width` caps the widest copy, and `quality` sets the encoding quality from 1 to 100. These default to the `[images]` section of assets.toml. `sizes` describes how wide the image is displayed, like the html `sizes` attribute, and also accepts `grid-N` widths. It defaults to `100vw`. `debug` can be `true` or `false`.
 
 include_image!(
     path_to_image: \"src/images/santoka.jpg\",
     alt: \"Taneda Santōka\",
     placeholder: lqip,
+    formats: [avif, webp, jpeg],
+    widths: [400, 800, 1200],
+    max_width: 2400,
+    densities: [1, 2],
+    quality: 70,
+    sizes: "(max-width: 640px) 100vw, grid-52",
     debug: true,
 );
+
+`crops` lists aspect ratio variants to generate, each with its own resized copies and placeholder. With `crops`, include_image! defines a struct instead of returning an ImageAsset, so it needs a `struct_name` and has to be called outside of any function. The struct has an ImageAsset field called `original`, one for each crop, and a `new` function for creating it. `focal_point` is where to center the crops, as fractions of the width and height from the top left, or `automatic` to find the most detailed part of the image. It defaults to the center.
+
+include_image!(
+    struct_name: Santoka,
+    path_to_image: "src/images/santoka.jpg",
+    alt: "Taneda Santōka",
+    crops: { card: "16:9", thumb: "1:1" },
+    focal_point: (0.5, 0.3),
+);
 "#;
-        let error = syn::Error::new(input.span(), error_message);
+        // The whole include_image! call, for errors that aren't about one argument.
+        let macro_call_span = Span::call_site();
+        let error = syn::Error::new(macro_call_span, error_message);
+
+        // This argument is only needed with crops, so we check for it
+        // once we know whether there are any.
+        let struct_name = parse_named_ident_argument("struct_name", &input);
 
         // This argument is required, so if it's not present we
         // convert None to an error and return early.
@@ -43,10 +81,52 @@ include_image!(
         let absolute_path_to_image = assets_runtime::paths::workspace_root_dir()
             .join(string_path_to_image_starting_at_workspace_root);
 
-        let alt = parse_alt(&input).ok_or(error.clone())?;
+        let (alt, alt_span) = parse_alt(&input)?;
+
+        // This argument is optional, so we default to `false` if it's not present.
+        let decorative = parse_named_bool_argument("decorative", &input).unwrap_or(false);
+
+        let alt = match (alt, decorative) {
+            (Alt::Literal(_) | Alt::Automatic, true) => {
+                return Err(syn::Error::new(
+                    alt_span,
+                    "Decorative images shouldn't have alt text. Please pass either `alt` or `decorative: true`, but not both.",
+                ))
+            }
+            (_, true) => Alt::Decorative,
+            (alt, false) => alt,
+        };
+
+        // Inaccessible images shouldn't compile.
+        let alt = resolve_alt(alt, &absolute_path_to_image)
+            .map_err(|message| syn::Error::new(alt_span, message))?;
 
         let placeholder_to_generate = parse_placeholder_to_generate(&input).ok_or(error)?;
 
+        let encoding_options = parse_encoding_options(&input)?;
+
+        let sizes = parse_sizes(&input)?;
+
+        let crops = parse_crops(&input)?;
+
+        let focal_point = parse_focal_point(&input)?;
+
+        match (&struct_name, crops.is_empty()) {
+            (None, false) => {
+                return Err(syn::Error::new(
+                    macro_call_span,
+                    "include_image! with crops defines a struct, so it needs a struct_name, like `struct_name: Santoka`.",
+                ))
+            }
+            (Some(struct_name), true) => {
+                return Err(syn::Error::new(
+                    struct_name.span(),
+                    "struct_name is only for include_image! with crops. Without crops, include_image! returns an ImageAsset.",
+                ))
+            }
+            _ => {}
+        }
+
         // This argument is optional, so we default to `false` if it's not present.
         let debug = parse_named_bool_argument("debug", &input).unwrap_or(false);
 
@@ -54,38 +134,62 @@ include_image!(
             absolute_path_to_image,
             alt,
             placeholder_to_generate,
+            encoding_options,
+            sizes,
+            struct_name,
+            crops,
+            focal_point,
             debug,
         })
     }
 }
 
-fn parse_alt(input: &ParseStream) -> Option<Alt> {
+// Returns the alt text along with the span to point at if it's wrong,
+// which is the value if there is one, or else the whole macro call.
+fn parse_alt(input: &ParseStream) -> SynResult<(Alt, Span)> {
     let maybe_argument_name = parse_argument_name_and_colon("alt", input);
 
-    // If there's no argument name, generate alt text automatically.
+    // If there's no argument name, look for the alt text next to the image.
     if maybe_argument_name.is_none() {
-        return Some(Alt::Automatic);
+        return Ok((Alt::FromSidecar, Span::call_site()));
     }
 
-    // If there's an argument name, the value should be a string literal
-    // containing the alt text.
-    let argument_value_literal: LitStr = input.parse().ok()?;
-    let alt_str = argument_value_literal.value();
-    let alt = Alt::Literal(alt_str);
+    // If there's an argument name, the value should either be `automatic`
+    // or a string literal containing the alt text.
+    let (alt, span) = if input.peek(Ident) {
+        let argument_value_ident: Ident = input.parse()?;
+        if argument_value_ident != "automatic" {
+            return Err(syn::Error::new(
+                argument_value_ident.span(),
+                "alt should be a string literal like `alt: \"Taneda Santōka\"`, or `automatic`.",
+            ));
+        }
+
+        (Alt::Automatic, argument_value_ident.span())
+    } else {
+        let argument_value_literal: LitStr = input.parse()?;
+        let alt = argument_value_literal.value();
+
+        // An empty alt attribute tells screen readers to skip the image,
+        // so it has to be a deliberate choice.
+        if alt.trim().is_empty() {
+            return Err(syn::Error::new(
+                argument_value_literal.span(),
+                "alt text can't be empty. If the image is purely decorative, pass `decorative: true` instead of `alt`.",
+            ));
+        }
+
+        (Alt::Literal(alt), argument_value_literal.span())
+    };
 
     // Parse the optional comma after the argument. Note how
     // we ignore the error here since the comma is optional.
     let _: Result<Token![,], _> = input.parse();
 
-    Some(alt)
+    Ok((alt, span))
 }
 
-pub enum Alt {
-    Automatic,
-    Literal(String),
-}
-
-fn parse_placeholder_to_generate(input: &ParseStream) -> Option<PlaceholderToGenerate> {
+pub fn parse_placeholder_to_generate(input: &ParseStream) -> Option<PlaceholderToGenerate> {
     let maybe_argument_name = parse_argument_name_and_colon("placeholder", input);
 
     if maybe_argument_name.is_none() {
@@ -99,8 +203,9 @@ fn parse_placeholder_to_generate(input: &ParseStream) -> Option<PlaceholderToGen
     let placeholder = match placeholder_str.as_str() {
         "lqip" => PlaceholderToGenerate::Lqip,
         "automatic_color" => PlaceholderToGenerate::AutomaticallyDetectedColor,
+        "thumbhash" => PlaceholderToGenerate::ThumbHash,
         _ => panic!(
-            "Invalid placeholder: {}. Should be either lqip or automatic_color.",
+            "Invalid placeholder: {}. Should be lqip, automatic_color, or thumbhash.",
             placeholder_str
         ),
     };
@@ -111,3 +216,55 @@ fn parse_placeholder_to_generate(input: &ParseStream) -> Option<PlaceholderToGen
 
     Some(placeholder)
 }
+
+pub fn parse_sizes(input: &ParseStream) -> SynResult<Sizes> {
+    // This argument is optional, so we default to the same thing
+    // browsers assume when there's no `sizes` attribute.
+    if parse_argument_name_and_colon("sizes", input).is_none() {
+        return Ok(Sizes::default());
+    }
+
+    let sizes_literal: LitStr = input.parse()?;
+    let sizes = sizes_literal
+        .value()
+        .parse()
+        .map_err(|error: String| syn::Error::new(sizes_literal.span(), error))?;
+
+    // Parse the optional comma after the argument. Note how
+    // we ignore the error here since the comma is optional.
+    let _: Result<Token![,], _> = input.parse();
+
+    Ok(sizes)
+}
+
+pub fn parse_formats(input: &ParseStream) -> SynResult<Vec<ImageFormat>> {
+    // This argument is optional, so we default to just JPEG if it's not present.
+    let Some(format_idents) = parse_named_ident_list_argument("formats", input)? else {
+        return Ok(vec![ImageFormat::Jpeg]);
+    };
+
+    let mut formats = format_idents
+        .iter()
+        .map(|format_ident| match format_ident.to_string().as_str() {
+            "avif" => Ok(ImageFormat::Avif),
+            "webp" => Ok(ImageFormat::WebP),
+            "jpeg" | "jpg" => Ok(ImageFormat::Jpeg),
+            "png" => Ok(ImageFormat::Png),
+            other => Err(syn::Error::new(
+                format_ident.span(),
+                format!(
+                    "Invalid format: {}. Should be avif, webp, jpeg, or png.",
+                    other
+                ),
+            )),
+        })
+        .collect::<SynResult<Vec<ImageFormat>>>()?;
+
+    // Every browser supports JPEG, so we always include it as a fallback.
+    // Images with transparency get PNG instead, in BuildTimeImage.
+    if !formats.contains(&ImageFormat::Jpeg) && !formats.contains(&ImageFormat::Png) {
+        formats.push(ImageFormat::Jpeg);
+    }
+
+    Ok(formats)
+}
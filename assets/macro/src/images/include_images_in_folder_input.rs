@@ -1,43 +1,77 @@
+use super::build_time_image::PlaceholderToGenerate;
+use super::encoding_options::{parse_encoding_options, EncodingOptions};
+use super::include_image_input::{parse_placeholder_to_generate, parse_sizes};
 use crate::parse_macro_arguments::*;
+use assets_runtime::Sizes;
 use std::path::PathBuf;
 use syn::{
     parse::{Parse, ParseStream},
-    Result as SynResult,
+    Ident, Result as SynResult,
 };
 
 /// This struct represents the input to the `include_images_in_folder!` macro.
 pub struct IncludeImagesInFolderInput {
+    pub struct_name: Ident,
     pub absolute_path_to_images_dir: PathBuf,
+    pub placeholder_to_generate: PlaceholderToGenerate,
+    pub encoding_options: EncodingOptions,
+    pub sizes: Sizes,
     pub debug: bool,
 }
 
 impl Parse for IncludeImagesInFolderInput {
     fn parse(input: ParseStream) -> SynResult<Self> {
-        let error_message = r#"Please make sure to pass arguments to include_images! like this:
+        let error_message = r#"Please make sure to pass arguments to include_images_in_folder! like this:
 
-include_images!(path_to_images_dir: \"src/original_images\");
+include_images_in_folder!(
+    struct_name: ProjectFlowers,
+    path_to_images_dir: \"src/images/project_flowers\",
+);
 
-The path should be relative to the workspace root.
+This generates a struct called ProjectFlowers with an ImageAsset field for each image in the folder, named after the image's file name, and a `new` function for creating it. Call it outside of any function, since it defines a struct. The path should be relative to the workspace root.
 
-You can also pass an optional `debug` argument like this:
+Alt text for each image comes from a sidecar file or an alt.toml file in the folder, just like include_image! without an `alt` argument.
 
-include_images!(path_to_images_dir: \"src/original_images\", debug: true);
+You can also pass the optional `placeholder`, `formats`, `widths`, `max_width`, `densities`, `quality`, `sizes`, and `debug` arguments from include_image!, which apply to every image in the folder:
+
+include_images_in_folder!(
+    struct_name: ProjectFlowers,
+    path_to_images_dir: \"src/images/project_flowers\",
+    placeholder: lqip,
+    formats: [avif, webp, jpeg],
+    widths: [400, 800],
+    densities: [1, 2],
+    sizes: \"grid-52\",
+    debug: true,
+);
 "#;
         let error = syn::Error::new(input.span(), error_message);
 
-        // This argument is required, so if it's not present we
+        // These arguments are required, so if they're not present we
         // convert None to an error and return early.
+        let struct_name = parse_named_ident_argument("struct_name", &input).ok_or(error.clone())?;
+
         let string_path_to_images_dir_starting_at_workspace_root =
-            parse_named_string_argument("path_to_images_dir", &input).ok_or(error)?;
+            parse_named_string_argument("path_to_images_dir", &input).ok_or(error.clone())?;
 
         let absolute_path_to_images_dir = assets_runtime::paths::workspace_root_dir()
             .join(string_path_to_images_dir_starting_at_workspace_root);
 
+        let placeholder_to_generate = parse_placeholder_to_generate(&input).ok_or(error)?;
+
+        let encoding_options = parse_encoding_options(&input)?;
+
+        let sizes = parse_sizes(&input)?;
+
         // This argument is optional, so we default to `false` if it's not present.
         let debug = parse_named_bool_argument("debug", &input).unwrap_or(false);
 
         Ok(IncludeImagesInFolderInput {
+            struct_name,
             absolute_path_to_images_dir,
+            placeholder_to_generate,
+            encoding_options,
+            sizes,
             debug,
         })
     }
@@ -1,22 +1,31 @@
+use alt_text::{resolve_alt, Alt};
 use assets_runtime::ImageAsset;
 use build_time_image::*;
-use image::DynamicImage;
+use crops::Crop;
 use image_asset_extension::*;
+use image_file::ImageFile;
 use include_image_input::*;
+use include_images_in_folder_input::*;
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
 use std::path::Path;
-// use include_images_in_folder_input::*;
-// use quote::format_ident;
-// use rayon::prelude::*;
-// use walkdir::WalkDir;
+use walkdir::WalkDir;
 
+mod alt_text;
 mod build_time_image;
 mod build_time_resized_image;
+mod captioning;
+mod crops;
 mod dynamic_image_extension;
+mod encoding_options;
 mod image_asset_extension;
+mod image_file;
+mod image_metadata;
 mod include_image_input;
 mod include_images_in_folder_input;
+mod palette;
 
 pub fn include_image(input: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(input as IncludeImageInput);
@@ -28,55 +37,83 @@ pub fn include_image(input: TokenStream) -> TokenStream {
         "Including image: {}",
         input.absolute_path_to_image.display()
     );
-    let image_file = try_get_image_file_from_path(absolute_path_to_image)
-        .expect("Error getting image file from path.");
+    let image_file =
+        ImageFile::open(absolute_path_to_image).expect("Error getting image file from path.");
 
     let absolute_path_to_image_dir = absolute_path_to_image
         .parent()
         .expect("Error getting parent directory of image.")
         .to_path_buf();
 
-    let alt = match input.alt {
-        Alt::Literal(alt) => alt,
-        Alt::Automatic => "fix me".to_string(), // image_captioner::get_caption(absolute_path_to_image).expect("Error getting image caption."),
+    let build_time_image_from_file = |image_file: &ImageFile| {
+        BuildTimeImage::new(
+            &absolute_path_to_image_dir,
+            image_file,
+            input.placeholder_to_generate,
+            &input.encoding_options,
+            input.sizes.clone(),
+            input.alt.clone(),
+        )
     };
 
-    let build_time_image = BuildTimeImage::new(
-        &absolute_path_to_image_dir,
-        absolute_path_to_image.clone(),
-        image_file.image,
-        input.placeholder_to_generate,
-        alt,
-    );
-
-    let image_asset = ImageAsset::from_build_time_image(&build_time_image);
+    // Errors here are about the image as a whole, like its sizes not being
+    // covered, so they point at the whole include_image! call.
+    let compile_error = |message: String| {
+        syn::Error::new(proc_macro2::Span::call_site(), message)
+            .to_compile_error()
+            .into()
+    };
 
-    let code = quote! {
-        #image_asset
+    let image_asset = match build_time_image_from_file(&image_file) {
+        Ok(build_time_image) => ImageAsset::from_build_time_image(&build_time_image),
+        Err(message) => return compile_error(message),
     };
 
-    // print_code_for_debugging(&code);
+    let Some(struct_name) = &input.struct_name else {
+        let code = quote! {
+            #image_asset
+        };
 
-    code.into()
-}
+        // print_code_for_debugging(&code);
+
+        return code.into();
+    };
 
-fn try_get_image_file_from_path(path: &Path) -> Option<ImageFile> {
-    match image::open(path) {
-        Ok(dynamic_image) => {
-            let image_file = ImageFile {
-                image: dynamic_image,
+    let focal_point = input.focal_point.resolve(&image_file.image);
+    log::info!("Cropping around focal point {:?}.", focal_point);
+
+    let crops = input
+        .crops
+        .iter()
+        .map(|(name, aspect_ratio)| {
+            let crop = Crop {
+                name: name.to_string(),
+                aspect_ratio: *aspect_ratio,
+                focal_point,
             };
-            Some(image_file)
-        }
-        Err(error) => {
-            log::info!("Error opening file as image {:?}: {:?}", path, error);
-            None
-        }
-    }
-}
+            let cropped_image_file = image_file.crop(&crop);
+            let build_time_image = build_time_image_from_file(&cropped_image_file)?;
+            Ok((
+                name.clone(),
+                ImageAsset::from_build_time_image(&build_time_image),
+            ))
+        })
+        .collect::<Result<Vec<_>, String>>();
+
+    let crops = match crops {
+        Ok(crops) => crops,
+        Err(message) => return compile_error(message),
+    };
+
+    let fields = std::iter::once((format_ident!("original"), image_asset))
+        .chain(crops)
+        .collect::<Vec<_>>();
+
+    let code = generate_struct(struct_name, &fields);
 
-struct ImageFile {
-    image: DynamicImage,
+    // print_code_for_debugging(&code);
+
+    code.into()
 }
 
 #[allow(dead_code)]
@@ -100,101 +137,179 @@ fn print_code_for_debugging(token_stream: &proc_macro2::TokenStream) {
     log::info!("{}", formatted_code);
 }
 
-// pub fn include_images_in_folder(input: TokenStream) -> TokenStream {
-//     let input = syn::parse_macro_input!(input as IncludeImagesInFolderInput);
-//     crate::logger::init_logger(input.debug);
-
-//     log::info!("Includeing images.");
-//     log::info!(
-//         "Path to images directory: {}",
-//         input.absolute_path_to_image_dir.display()
-//     );
-//     let images_to_include = get_images_from_disk(input);
-//     let code = generate_code(&images_to_include);
-
-//     // print_code_for_debugging(&code);
-
-//     code.into()
-// }
-
-// We wrap our built images in Arcs because we need owned copies
-// to use Rayon, and we presume that cloning BuiltImages is expensive
-// because they hold giant piles of image bytes.
-// fn get_images_from_disk(input: IncludeImagesInFolderInput) -> Vec<BuildTimeImage> {
-//     log::info!("Getting original images from disk.");
-//     let original_images = get_image_files(&input.absolute_path_to_images_dir);
-//     log::info!("Found {} original images.", original_images.len());
-
-//     log::info!("Generating placeholders and saving images to disk if necessary.");
-//     original_images
-//         .into_par_iter()
-//         .map(|image_file| {
-//             BuildTimeImage::new(
-//                 &input.absolute_path_to_images_dir,
-//                 image_file.absolute_path_to_image,
-//                 image_file.image,
-//                 PlaceholderToGenerate::Lqip,
-//                 "fix me".to_string(),
-//             )
-//         })
-//         .collect()
-// }
-
-// fn get_image_files(path_to_images_dir: &Path) -> Vec<ImageFile> {
-//     WalkDir::new(path_to_images_dir)
-//         .into_iter()
-//         .filter_map(try_get_image_file_from_dir_entry)
-//         .collect()
-// }
-
-// fn try_get_image_file_from_dir_entry(
-//     maybe_dir_entry: walkdir::Result<walkdir::DirEntry>,
-// ) -> Option<ImageFile> {
-//     match maybe_dir_entry {
-//         Ok(entry) if entry.file_type().is_dir() => None,
-//         Ok(entry) => {
-//             let path = entry.path();
-//             try_get_image_file_from_path(path)
-//         }
-//         Err(error) => {
-//             log::info!("Error reading image file: {:?}", error);
-//             None
-//         }
-//     }
-// }
-
-// fn generate_code(images_to_include: &[BuildTimeImage]) -> proc_macro2::TokenStream {
-//     log::info!("Generating code for built images.");
-
-//     log::info!("Building property names.");
-//     let image_property_names: Vec<_> = images_to_include
-//         .iter()
-//         .map(|image| format_ident!("{}", image.name_in_source_code))
-//         .collect();
-
-//     log::info!("Instantiating ImageAssets.");
-//     let image_property_declarations = images_to_include.iter().map(|image| {
-//         let name_in_source_code = format_ident!("{}", image.name_in_source_code);
-//         let image_asset = ImageAsset::from_build_time_image(image);
-//         quote! {
-//             #name_in_source_code: #image_asset,
-//         }
-//     });
-
-//     quote! {
-//         {
-//             use std::path::PathBuf;
-//             use std::str::FromStr;
-
-//             pub struct ImageAssets {
-//                 #(
-//                     pub #image_property_names: ImageAsset,
-//                 )*
-//             }
-
-//             ImageAssets {
-//                 #( #image_property_declarations )*
-//             }
-//         }
-//     }
-// }
+pub fn include_images_in_folder(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as IncludeImagesInFolderInput);
+    crate::logger::init_logger(input.debug);
+
+    log::info!("Including images.");
+    log::info!(
+        "Path to images directory: {}",
+        input.absolute_path_to_images_dir.display()
+    );
+
+    let images_to_include = match get_images_from_disk(&input) {
+        Ok(images_to_include) => images_to_include,
+        Err(message) => {
+            return syn::Error::new(input.struct_name.span(), message)
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let code = generate_code(&input.struct_name, &images_to_include);
+
+    // print_code_for_debugging(&code);
+
+    code.into()
+}
+
+fn get_images_from_disk(input: &IncludeImagesInFolderInput) -> Result<Vec<BuildTimeImage>, String> {
+    log::info!("Getting original images from disk.");
+    let original_images = get_image_files(&input.absolute_path_to_images_dir);
+    log::info!("Found {} original images.", original_images.len());
+
+    if original_images.is_empty() {
+        return Err(format!(
+            "Couldn't find any images in {}.",
+            input.absolute_path_to_images_dir.display()
+        ));
+    }
+
+    // Built images go in a folder named after the images folder,
+    // so that images in different folders can share file names.
+    let path_to_images_dir_parent = input
+        .absolute_path_to_images_dir
+        .parent()
+        .expect("Error getting parent directory of images folder.");
+
+    // Idents aren't thread safe, so we only share the
+    // parts of the input that we need with Rayon.
+    let placeholder_to_generate = input.placeholder_to_generate;
+    let encoding_options = &input.encoding_options;
+    let sizes = &input.sizes;
+
+    log::info!("Generating placeholders and saving images to disk if necessary.");
+    let images = original_images
+        .into_par_iter()
+        .map(|image_file| {
+            // Every image needs alt text, or has to be marked as decorative.
+            let alt = resolve_alt(Alt::FromSidecar, &image_file.absolute_path_to_image)?;
+
+            BuildTimeImage::new(
+                path_to_images_dir_parent,
+                &image_file,
+                placeholder_to_generate,
+                encoding_options,
+                sizes.clone(),
+                alt,
+            )
+        })
+        .collect::<Result<Vec<BuildTimeImage>, String>>()?;
+
+    check_for_duplicate_names(&images)?;
+
+    Ok(images)
+}
+
+fn get_image_files(path_to_images_dir: &Path) -> Vec<ImageFile> {
+    // Sorting keeps the generated struct the same between builds.
+    WalkDir::new(path_to_images_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(try_get_image_file_from_dir_entry)
+        .collect()
+}
+
+fn try_get_image_file_from_dir_entry(
+    maybe_dir_entry: walkdir::Result<walkdir::DirEntry>,
+) -> Option<ImageFile> {
+    match maybe_dir_entry {
+        Ok(entry) if entry.file_type().is_dir() => None,
+        Ok(entry) => {
+            let path = entry.path();
+            ImageFile::open(path)
+        }
+        Err(error) => {
+            log::info!("Error reading image file: {:?}", error);
+            None
+        }
+    }
+}
+
+// Field names come from file names, so two images in different
+// subfolders with the same name would end up with the same field.
+fn check_for_duplicate_names(images: &[BuildTimeImage]) -> Result<(), String> {
+    let mut paths_by_name: BTreeMap<&str, &Path> = BTreeMap::new();
+
+    for image in images {
+        let path = image.absolute_path_to_original_image.as_path();
+
+        if let Some(existing_path) = paths_by_name.insert(&image.name_in_source_code, path) {
+            return Err(format!(
+                "{} and {} would both be called {}. Please rename one of them.",
+                existing_path.display(),
+                path.display(),
+                image.name_in_source_code
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn generate_code(
+    struct_name: &syn::Ident,
+    images_to_include: &[BuildTimeImage],
+) -> proc_macro2::TokenStream {
+    log::info!("Generating code for built images.");
+
+    log::info!("Instantiating ImageAssets.");
+    let fields = images_to_include
+        .iter()
+        .map(|image| {
+            (
+                format_ident!("{}", image.name_in_source_code),
+                ImageAsset::from_build_time_image(image),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    generate_struct(struct_name, &fields)
+}
+
+// A struct with an ImageAsset field for each image, used for both
+// folders of images and an image's crops.
+fn generate_struct(
+    struct_name: &syn::Ident,
+    fields: &[(syn::Ident, ImageAsset)],
+) -> proc_macro2::TokenStream {
+    let image_property_names = fields.iter().map(|(name, _)| name);
+
+    let image_property_declarations = fields.iter().map(|(name, image_asset)| {
+        quote! {
+            #name: #image_asset,
+        }
+    });
+
+    quote! {
+        pub struct #struct_name {
+            #(
+                pub #image_property_names: assets::ImageAsset,
+            )*
+        }
+
+        impl #struct_name {
+            pub fn new() -> Self {
+                Self {
+                    #( #image_property_declarations )*
+                }
+            }
+        }
+
+        impl Default for #struct_name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    }
+}
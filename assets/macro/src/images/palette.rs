@@ -0,0 +1,155 @@
+use assets_runtime::Color;
+use image::DynamicImage;
+
+// Extracts the most common colors in an image using median cut. We start
+// with every pixel in one box, then repeatedly split the box with the
+// widest range of colors in half along its widest channel. Each final box
+// becomes one color.
+//
+// Median cut splits every box in half, so the boxes all end up with about
+// the same number of pixels, even in an image that's mostly one color.
+// So to order the palette, we count how many pixels are nearest to each
+// color instead, and the colors with the most pixels come first.
+//
+// Averaging the whole image instead tends to give a muddy grey for
+// colorful images, since the colors cancel each other out.
+pub fn extract_palette(image: &DynamicImage, color_count: usize) -> Vec<Color> {
+    let pixels = visible_pixels(image);
+    if pixels.is_empty() {
+        return vec![];
+    }
+
+    let mut boxes = vec![pixels.clone()];
+
+    while boxes.len() < color_count {
+        let Some(index_to_split) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, pixels)| pixels.len() > 1)
+            .max_by_key(|(_, pixels)| widest_channel(pixels).1)
+            .map(|(index, _)| index)
+        else {
+            // Every box is a single pixel, so there's nothing left to split.
+            break;
+        };
+
+        let pixels = boxes.swap_remove(index_to_split);
+        let (first_half, second_half) = split_at_median(pixels);
+        boxes.push(first_half);
+        boxes.push(second_half);
+    }
+
+    let mut palette: Vec<[u8; 3]> = vec![];
+    for color in boxes.iter().map(|pixels| average(pixels)) {
+        // Boxes split from one flat area average to the same color.
+        if !palette.contains(&color) {
+            palette.push(color);
+        }
+    }
+
+    let mut pixel_counts = vec![0_usize; palette.len()];
+    for pixel in &pixels {
+        pixel_counts[nearest_color_index(&palette, pixel)] += 1;
+    }
+
+    let mut palette_with_counts: Vec<([u8; 3], usize)> =
+        palette.into_iter().zip(pixel_counts).collect();
+    palette_with_counts.sort_by_key(|(_, pixel_count)| std::cmp::Reverse(*pixel_count));
+
+    palette_with_counts
+        .into_iter()
+        .map(|([red, green, blue], _)| Color::new(red, green, blue))
+        .collect()
+}
+
+fn nearest_color_index(palette: &[[u8; 3]], pixel: &[u8; 3]) -> usize {
+    let distance = |color: &[u8; 3]| -> u32 {
+        color
+            .iter()
+            .zip(pixel)
+            .map(|(a, b)| (*a as i32 - *b as i32).pow(2) as u32)
+            .sum()
+    };
+
+    (0..palette.len())
+        .min_by_key(|index| distance(&palette[*index]))
+        .unwrap()
+}
+
+// Shrinks the image first since we don't need every pixel to find the
+// main colors. Mostly transparent pixels are skipped, since their color
+// isn't actually visible.
+fn visible_pixels(image: &DynamicImage) -> Vec<[u8; 3]> {
+    image
+        .thumbnail(100, 100)
+        .to_rgba8()
+        .pixels()
+        .filter(|pixel| pixel.0[3] >= 128)
+        .map(|pixel| [pixel.0[0], pixel.0[1], pixel.0[2]])
+        .collect()
+}
+
+// Returns the channel with the widest range of values, and that range.
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let values = pixels.iter().map(|pixel| pixel[channel]);
+            let min = values.clone().min().unwrap_or(0);
+            let max = values.max().unwrap_or(0);
+            (channel, max - min)
+        })
+        .max_by_key(|(_, range)| *range)
+        .unwrap()
+}
+
+fn split_at_median(mut pixels: Vec<[u8; 3]>) -> (Vec<[u8; 3]>, Vec<[u8; 3]>) {
+    let (channel, _) = widest_channel(&pixels);
+    pixels.sort_unstable_by_key(|pixel| pixel[channel]);
+
+    let second_half = pixels.split_off(pixels.len() / 2);
+    (pixels, second_half)
+}
+
+fn average(pixels: &[[u8; 3]]) -> [u8; 3] {
+    let mut totals = [0_u64; 3];
+    for pixel in pixels {
+        for (total, value) in totals.iter_mut().zip(pixel) {
+            *total += *value as u64;
+        }
+    }
+
+    let count = pixels.len() as u64;
+    totals.map(|total| (total / count) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn mostly_one_color_comes_first() {
+        // 90% red, with a blue stripe down the right side.
+        let image = RgbaImage::from_fn(100, 100, |x, _| {
+            if x < 90 {
+                Rgba([255, 0, 0, 255])
+            } else {
+                Rgba([0, 0, 255, 255])
+            }
+        });
+
+        let palette = extract_palette(&DynamicImage::ImageRgba8(image), 8);
+
+        assert_eq!(palette[0], Color::new(255, 0, 0));
+        assert!(palette.contains(&Color::new(0, 0, 255)));
+    }
+
+    #[test]
+    fn skips_transparent_pixels() {
+        let image = RgbaImage::from_pixel(10, 10, Rgba([255, 0, 0, 0]));
+
+        let palette = extract_palette(&DynamicImage::ImageRgba8(image), 4);
+
+        assert!(palette.is_empty());
+    }
+}
@@ -1,18 +1,31 @@
 use proc_macro::TokenStream;
 
 mod browser_crate;
+mod compression;
+mod config;
 mod file;
 mod font;
+mod font_subset;
 mod images;
 mod logger;
 mod parse_macro_arguments;
+mod stale_built_assets;
+mod svg;
 mod tailwind;
+mod tailwind_classes;
 
 #[proc_macro]
 pub fn include_tailwind(input: TokenStream) -> TokenStream {
     tailwind::include(input)
 }
 
+/// Checks at compile time that every class in a string is in the
+/// CSS Tailwind generates, and expands to the string itself.
+#[proc_macro]
+pub fn class(input: TokenStream) -> TokenStream {
+    tailwind_classes::check(input)
+}
+
 #[proc_macro]
 pub fn include_browser_crate(input: TokenStream) -> TokenStream {
     browser_crate::include(input)
@@ -23,6 +36,11 @@ pub fn include_image(input: TokenStream) -> TokenStream {
     images::include_image(input)
 }
 
+#[proc_macro]
+pub fn include_images_in_folder(input: TokenStream) -> TokenStream {
+    images::include_images_in_folder(input)
+}
+
 #[proc_macro]
 pub fn include_font(input: TokenStream) -> TokenStream {
     font::include(input)
@@ -33,6 +51,16 @@ pub fn include_file(input: TokenStream) -> TokenStream {
     file::include(input)
 }
 
+#[proc_macro]
+pub fn include_svg(input: TokenStream) -> TokenStream {
+    svg::include(input)
+}
+
+#[proc_macro]
+pub fn remove_stale_built_assets(input: TokenStream) -> TokenStream {
+    stale_built_assets::remove(input)
+}
+
 // #[proc_macro]
 // pub fn include_images(input: TokenStream) -> TokenStream {
 //     lock_file::with_lock_file("include_images", || images::include_images_in_folder(input))
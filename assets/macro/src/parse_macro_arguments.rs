@@ -1,5 +1,9 @@
 use assets_runtime::built_assets_browser_prefix;
-use syn::{parse::ParseStream, Ident, LitBool, LitInt, LitStr, Token};
+use syn::{
+    parse::{ParseStream, Parser},
+    punctuated::Punctuated,
+    Ident, LitBool, LitInt, LitStr, Token,
+};
 
 pub fn parse_named_string_argument(
     argument_name: &'static str,
@@ -44,6 +48,156 @@ pub fn parse_named_u64_argument(argument_name: &'static str, input: &ParseStream
     Some(argument_value)
 }
 
+/// parse_named_ident_argument("struct_name", input)
+/// will parse the following input:
+/// ```text
+///    struct_name: ProjectFlowers,
+/// ```
+pub fn parse_named_ident_argument(
+    argument_name: &'static str,
+    input: &ParseStream,
+) -> Option<Ident> {
+    parse_argument_name_and_colon(argument_name, input)?;
+
+    // Parse the argument value.
+    let argument_value: Ident = input.parse().ok()?;
+
+    // Parse the optional comma after the argument. Note how
+    // we ignore the error here since the comma is optional.
+    let _: Result<Token![,], _> = input.parse();
+
+    Some(argument_value)
+}
+
+/// parse_named_ident_list_argument("formats", input)
+/// will parse the following input:
+/// ```text
+///    formats: [avif, webp, jpeg],
+/// ```
+///
+/// Returns `Ok(None)` if the argument isn't there, and an error pointing
+/// at the value if it's there but isn't a list of idents.
+pub fn parse_named_ident_list_argument(
+    argument_name: &'static str,
+    input: &ParseStream,
+) -> syn::Result<Option<Vec<Ident>>> {
+    if parse_argument_name_and_colon(argument_name, input).is_none() {
+        return Ok(None);
+    }
+
+    let error_message = format!(
+        "{} should be a list of names in square brackets, like [avif, webp, jpeg].",
+        argument_name
+    );
+
+    // Parse the square brackets and the comma-separated idents inside them.
+    let group: proc_macro2::Group = input
+        .parse()
+        .map_err(|error| syn::Error::new(error.span(), &error_message))?;
+    if group.delimiter() != proc_macro2::Delimiter::Bracket {
+        return Err(syn::Error::new(group.span(), error_message));
+    }
+    let idents = Punctuated::<Ident, Token![,]>::parse_terminated
+        .parse2(group.stream())
+        .map_err(|error| syn::Error::new(error.span(), &error_message))?;
+
+    // Parse the optional comma after the argument. Note how
+    // we ignore the error here since the comma is optional.
+    let _: Result<Token![,], _> = input.parse();
+
+    Ok(Some(idents.into_iter().collect()))
+}
+
+/// parse_named_int_argument("max_width", input)
+/// will parse the following input:
+/// ```text
+///    max_width: 2400,
+/// ```
+///
+/// Returns `Ok(None)` if the argument isn't there, and an error pointing
+/// at the value if it's there but isn't an integer. Use `parse_positive_u32`
+/// or `LitInt::base10_parse` to get the number, with errors on its span.
+pub fn parse_named_int_argument(
+    argument_name: &'static str,
+    input: &ParseStream,
+) -> syn::Result<Option<LitInt>> {
+    if parse_argument_name_and_colon(argument_name, input).is_none() {
+        return Ok(None);
+    }
+
+    // Parse the argument value.
+    let argument_value_literal: LitInt = input.parse().map_err(|error| {
+        syn::Error::new(
+            error.span(),
+            format!("{} should be a number, like 800.", argument_name),
+        )
+    })?;
+
+    // Parse the optional comma after the argument. Note how
+    // we ignore the error here since the comma is optional.
+    let _: Result<Token![,], _> = input.parse();
+
+    Ok(Some(argument_value_literal))
+}
+
+/// parse_named_u32_list_argument("widths", input)
+/// will parse the following input:
+/// ```text
+///    widths: [400, 800, 1200],
+/// ```
+///
+/// Returns `Ok(None)` if the argument isn't there, and an error pointing
+/// at the value if it's there but isn't a list of positive numbers that
+/// fit in a u32.
+pub fn parse_named_u32_list_argument(
+    argument_name: &'static str,
+    input: &ParseStream,
+) -> syn::Result<Option<Vec<u32>>> {
+    if parse_argument_name_and_colon(argument_name, input).is_none() {
+        return Ok(None);
+    }
+
+    let error_message = format!(
+        "{} should be a list of numbers in square brackets, like [400, 800, 1200].",
+        argument_name
+    );
+
+    // Parse the square brackets and the comma-separated integers inside them.
+    let group: proc_macro2::Group = input
+        .parse()
+        .map_err(|error| syn::Error::new(error.span(), &error_message))?;
+    if group.delimiter() != proc_macro2::Delimiter::Bracket {
+        return Err(syn::Error::new(group.span(), error_message));
+    }
+    let integers = Punctuated::<LitInt, Token![,]>::parse_terminated
+        .parse2(group.stream())
+        .map_err(|error| syn::Error::new(error.span(), &error_message))?
+        .iter()
+        .map(|integer| parse_positive_u32(argument_name, integer))
+        .collect::<syn::Result<Vec<u32>>>()?;
+
+    // Parse the optional comma after the argument. Note how
+    // we ignore the error here since the comma is optional.
+    let _: Result<Token![,], _> = input.parse();
+
+    Ok(Some(integers))
+}
+
+/// Parses an integer literal that should be between 1 and `u32::MAX`,
+/// with an error pointing at the literal if it isn't.
+pub fn parse_positive_u32(argument_name: &'static str, integer: &LitInt) -> syn::Result<u32> {
+    integer
+        .base10_parse::<u32>()
+        .ok()
+        .filter(|integer| *integer > 0)
+        .ok_or_else(|| {
+            syn::Error::new(
+                integer.span(),
+                format!("{} should be between 1 and {}.", argument_name, u32::MAX),
+            )
+        })
+}
+
 pub enum ParseUrlPathArgumentError {
     MissingArgument,
     InvalidPrefix,
@@ -90,20 +244,24 @@ pub fn parse_url_path_argument(
 
 /// parse_argument_name_and_colon("path_to_image", input)
 /// will parse the following input:
-/// ```
+/// ```text
 ///    path_to_image:
 /// ```
-/// It returns `None` if the input doesn't match.
+/// It returns `None` if the input doesn't match, without consuming
+/// anything, so that optional arguments can be skipped.
 pub fn parse_argument_name_and_colon(
     argument_name: &'static str,
     input: &ParseStream,
 ) -> Option<()> {
-    // Parse the argument name.
-    let parsed_argument_name: Ident = input.parse().ok()?;
-    if parsed_argument_name != argument_name {
+    // Peek at the argument name before parsing it.
+    let (next_ident, _) = input.cursor().ident()?;
+    if next_ident != argument_name {
         return None;
     }
 
+    // Parse the argument name.
+    let _: Ident = input.parse().ok()?;
+
     // Parse the colon.
     let _: Token![:] = input.parse().ok()?;
 
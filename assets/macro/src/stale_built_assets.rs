@@ -0,0 +1,125 @@
+use crate::parse_macro_arguments::*;
+use assets_runtime::paths::*;
+use proc_macro::TokenStream;
+use quote::quote;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use syn::{
+    parse::{Parse, ParseStream},
+    Result as SynResult,
+};
+use walkdir::WalkDir;
+
+// Every file our asset macros write to the built assets directory
+// during the current compile. Proc macros are loaded once per crate
+// that uses them, so this lives for the whole compile of that crate.
+//
+// Nothing tells us when a compile ends, so in a long-lived proc macro
+// server like rust-analyzer's, or after a partial expansion, this can be
+// out of date. That's why we only delete files when a full build asks
+// us to with this environment variable. See `./scripts clean_built_assets`.
+static DELETE_ENV_VAR: &str = "REMOVE_STALE_BUILT_ASSETS";
+static OUTPUTS_FROM_CURRENT_COMPILE: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// Record that the current compile produced a file in the built assets
+/// directory, so that `remove_stale_built_assets!` leaves it alone.
+pub fn register_output(path: &Path) {
+    log::info!("Registering built asset {}.", path.display());
+
+    OUTPUTS_FROM_CURRENT_COMPILE
+        .lock()
+        .expect("Error locking the list of built assets.")
+        .insert(path.to_path_buf());
+}
+
+pub fn remove(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as RemoveStaleBuiltAssetsInput);
+    crate::logger::init_logger(input.debug);
+
+    let outputs = OUTPUTS_FROM_CURRENT_COMPILE
+        .lock()
+        .expect("Error locking the list of built assets.")
+        .clone();
+
+    // If nothing was registered, this macro was probably expanded on its own
+    // (e.g. by an editor), and we'd end up deleting everything.
+    if outputs.is_empty() {
+        log::warn!("No built assets were registered during this compile, so not removing anything. Make sure remove_stale_built_assets! comes after the other asset macros.");
+        return quote! {}.into();
+    }
+
+    let stale_files = stale_files(&built_assets_dir(), &outputs);
+
+    let delete = std::env::var(DELETE_ENV_VAR).is_ok_and(|value| value == "1");
+
+    if !delete {
+        for stale_file in &stale_files {
+            log::warn!(
+                "Stale built asset {}. Run `./scripts clean_built_assets` to remove it.",
+                stale_file.display()
+            );
+        }
+    } else {
+        for stale_file in &stale_files {
+            log::info!("Removing stale built asset {}.", stale_file.display());
+            std::fs::remove_file(stale_file).expect("Error removing stale built asset.");
+        }
+
+        remove_empty_dirs(&built_assets_dir());
+    }
+
+    quote! {}.into()
+}
+
+fn stale_files(built_assets_dir: &Path, outputs: &BTreeSet<PathBuf>) -> Vec<PathBuf> {
+    WalkDir::new(built_assets_dir)
+        .into_iter()
+        .filter_map(|maybe_entry| match maybe_entry {
+            Ok(entry) if entry.file_type().is_file() => Some(entry.into_path()),
+            Ok(_) => None,
+            Err(error) => {
+                log::info!("Error reading built asset: {:?}", error);
+                None
+            }
+        })
+        .filter(|path| !outputs.contains(path))
+        .collect()
+}
+
+fn remove_empty_dirs(built_assets_dir: &Path) {
+    // contents_first visits the deepest folders first, so nested
+    // empty folders get removed before their parents are checked.
+    let dirs = WalkDir::new(built_assets_dir)
+        .min_depth(1)
+        .contents_first(true)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_dir());
+
+    for dir in dirs {
+        let is_empty = std::fs::read_dir(dir.path())
+            .map(|mut entries| entries.next().is_none())
+            .unwrap_or(false);
+
+        if is_empty {
+            log::info!(
+                "Removing empty built assets folder {}.",
+                dir.path().display()
+            );
+            std::fs::remove_dir(dir.path()).expect("Error removing empty built assets folder.");
+        }
+    }
+}
+
+struct RemoveStaleBuiltAssetsInput {
+    debug: bool,
+}
+
+impl Parse for RemoveStaleBuiltAssetsInput {
+    fn parse(input: ParseStream) -> SynResult<Self> {
+        let debug = parse_named_bool_argument("debug", &input).unwrap_or(false);
+
+        Ok(RemoveStaleBuiltAssetsInput { debug })
+    }
+}
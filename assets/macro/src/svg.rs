@@ -0,0 +1,607 @@
+use crate::parse_macro_arguments::*;
+use assets_runtime::{paths::*, SvgAsset};
+use proc_macro::TokenStream;
+use quote::quote;
+use std::str::FromStr;
+use std::{path::PathBuf, time::Duration};
+use syn::{
+    parse::{Parse, ParseStream},
+    Result as SynResult,
+};
+
+static SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+static XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+static XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+// Design tools export coordinates like 12.000000381, which is far more
+// precise than anything a screen can show.
+static DECIMAL_PLACES: usize = 3;
+
+// Elements that are only there for editors and search engines. We leave out
+// <title> too, since the Svg component adds its own for accessibility.
+static ELEMENTS_TO_REMOVE: [&str; 3] = ["metadata", "title", "desc"];
+
+// Attributes that hold numbers or lists of numbers, which we round.
+static NUMERIC_ATTRIBUTES: [&str; 19] = [
+    "d",
+    "points",
+    "transform",
+    "x",
+    "y",
+    "x1",
+    "y1",
+    "x2",
+    "y2",
+    "cx",
+    "cy",
+    "r",
+    "rx",
+    "ry",
+    "width",
+    "height",
+    "stroke-width",
+    "offset",
+    "viewBox",
+];
+
+// Attributes on the root element that don't make sense once the SVG is
+// optimized. The viewBox is kept separately, and width and height are
+// left to CSS so the SVG scales with its container.
+static ROOT_ATTRIBUTES_TO_REMOVE: [&str; 7] = [
+    "viewBox",
+    "width",
+    "height",
+    "version",
+    "x",
+    "y",
+    "enable-background",
+];
+
+pub fn include(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as IncludeSvgInput);
+    crate::logger::init_logger(input.debug);
+
+    log::info!("Including SVG: {}", input.path_to_input_file.display());
+
+    let input_file_path = workspace_root_dir().join(&input.path_to_input_file);
+
+    let svg_asset = match optimize_svg(&input_file_path, input.url_path, input.performance_budget) {
+        Ok(svg_asset) => svg_asset,
+        Err(message) => {
+            return syn::Error::new(input.span, message)
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let output_file_path = output_file_path(&svg_asset.url_path);
+
+    let output_dir = output_file_path
+        .parent()
+        .expect("Error getting SVG directory.");
+
+    std::fs::create_dir_all(output_dir).expect("Error creating built assets dir.");
+
+    std::fs::write(&output_file_path, svg_asset.to_file_contents())
+        .expect("Error writing SVG file.");
+    crate::stale_built_assets::register_output(&output_file_path);
+
+    let output = quote! {
+        #svg_asset
+    };
+
+    output.into()
+}
+
+fn optimize_svg(
+    input_file_path: &std::path::Path,
+    url_path: PathBuf,
+    performance_budget: Duration,
+) -> Result<SvgAsset, String> {
+    let text = std::fs::read_to_string(input_file_path)
+        .map_err(|error| format!("Error reading {}: {}", input_file_path.display(), error))?;
+
+    // Some design tools add a DOCTYPE, which roxmltree rejects by default.
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..roxmltree::ParsingOptions::default()
+    };
+    let document = roxmltree::Document::parse_with_options(&text, options)
+        .map_err(|error| format!("Error parsing {}: {}", input_file_path.display(), error))?;
+
+    let root = document.root_element();
+    if root.tag_name().name() != "svg" {
+        return Err(format!(
+            "{} should have an <svg> root element.",
+            input_file_path.display()
+        ));
+    }
+
+    let view_box = check_view_box(input_file_path, root)?;
+
+    let root_attributes = kept_attributes(root)
+        .into_iter()
+        .filter(|(name, _)| !ROOT_ATTRIBUTES_TO_REMOVE.contains(&name.as_str()))
+        .collect();
+
+    let mut inner_markup = String::new();
+    for child in root.children() {
+        write_node(child, &mut inner_markup);
+    }
+
+    let original_size = text.len();
+    let svg_asset = SvgAsset::new(
+        url_path,
+        performance_budget,
+        view_box,
+        root_attributes,
+        inner_markup,
+    );
+    log::info!(
+        "Optimized SVG from {} to {} bytes.",
+        original_size,
+        svg_asset.size_in_bytes
+    );
+
+    Ok(svg_asset)
+}
+
+// Without a viewBox, an SVG can't be scaled with CSS, which is almost
+// always what we want, so we require one.
+fn check_view_box(
+    input_file_path: &std::path::Path,
+    root: roxmltree::Node,
+) -> Result<String, String> {
+    let Some(view_box) = root.attribute("viewBox") else {
+        let suggestion = match (root.attribute("width"), root.attribute("height")) {
+            (Some(width), Some(height)) => format!(
+                " Based on its width and height, it should probably be viewBox=\"0 0 {} {}\".",
+                width.trim_end_matches("px"),
+                height.trim_end_matches("px")
+            ),
+            _ => String::new(),
+        };
+
+        return Err(format!(
+            "{} needs a viewBox on its <svg> element so that it can be scaled.{}",
+            input_file_path.display(),
+            suggestion
+        ));
+    };
+
+    let numbers: Vec<f64> = view_box
+        .split(|character: char| character.is_whitespace() || character == ',')
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| {
+            format!(
+                "{} has an invalid viewBox \"{}\".",
+                input_file_path.display(),
+                view_box
+            )
+        })?;
+
+    match numbers[..] {
+        [_, _, width, height] if width > 0.0 && height > 0.0 => Ok(round_numbers(view_box)),
+        _ => Err(format!(
+            "{} has an invalid viewBox \"{}\". It should be four numbers, like \"0 0 24 24\", with a positive width and height.",
+            input_file_path.display(),
+            view_box
+        )),
+    }
+}
+
+fn write_node(node: roxmltree::Node, output: &mut String) {
+    if node.is_text() {
+        // Whitespace between elements doesn't affect rendering.
+        let text = node.text().unwrap_or_default();
+        if !text.trim().is_empty() {
+            output.push_str(&escape(text, false));
+        }
+        return;
+    }
+
+    // This skips comments and processing instructions too.
+    if !node.is_element() {
+        return;
+    }
+
+    let tag_name = node.tag_name();
+    let is_svg_element = match tag_name.namespace() {
+        None => true,
+        Some(namespace) => namespace == SVG_NAMESPACE,
+    };
+    // Editors like Inkscape add elements in their own namespaces.
+    if !is_svg_element || ELEMENTS_TO_REMOVE.contains(&tag_name.name()) {
+        return;
+    }
+
+    let attributes = kept_attributes(node);
+
+    // Groups without attributes don't do anything, so we
+    // write their children in their place.
+    if tag_name.name() == "g" && attributes.is_empty() {
+        for child in node.children() {
+            write_node(child, output);
+        }
+        return;
+    }
+
+    output.push('<');
+    output.push_str(tag_name.name());
+    for (name, value) in &attributes {
+        output.push_str(&format!(" {}=\"{}\"", name, value));
+    }
+
+    let mut children = String::new();
+    for child in node.children() {
+        write_node(child, &mut children);
+    }
+
+    if children.is_empty() {
+        output.push_str("/>");
+    } else {
+        output.push('>');
+        output.push_str(&children);
+        output.push_str(&format!("</{}>", tag_name.name()));
+    }
+}
+
+// The attributes worth keeping, with numbers rounded and values escaped.
+fn kept_attributes(node: roxmltree::Node) -> Vec<(String, String)> {
+    node.attributes()
+        .filter_map(|attribute| {
+            let name = match attribute.namespace() {
+                None => attribute.name().to_string(),
+                // SVG 2 supports plain href, so we don't need the xlink namespace.
+                Some(namespace) if namespace == XLINK_NAMESPACE && attribute.name() == "href" => {
+                    "href".to_string()
+                }
+                Some(namespace) if namespace == XML_NAMESPACE => {
+                    format!("xml:{}", attribute.name())
+                }
+                // Editor specific attributes, like inkscape:label.
+                Some(_) => return None,
+            };
+
+            let value = if name == "d" {
+                round_path_data(attribute.value())
+            } else if NUMERIC_ATTRIBUTES.contains(&name.as_str()) {
+                round_numbers(attribute.value())
+            } else {
+                attribute.value().to_string()
+            };
+
+            Some((name, escape(&value, true)))
+        })
+        .collect()
+}
+
+// Rounds every number in a string like a transform or a list of points,
+// keeping everything between the numbers as is.
+fn round_numbers(value: &str) -> String {
+    let characters: Vec<char> = value.chars().collect();
+    let mut output = String::new();
+    let mut index = 0;
+
+    while index < characters.len() {
+        if starts_number(&characters, index) {
+            let number = read_number(&characters, &mut index);
+            push_number(&mut output, &round_number(&number));
+        } else {
+            output.push(characters[index]);
+            index += 1;
+        }
+    }
+
+    output
+}
+
+// Like round_numbers, but for a path's d attribute. Paths can run numbers
+// together, like "M1.5.5", which is 1.5 followed by .5. The flags in an
+// arc command are always a single 0 or 1, so "a10 10 0 0110 10" has the
+// flags 0 and 1 followed by 10, and we have to keep them as they are.
+fn round_path_data(value: &str) -> String {
+    let characters: Vec<char> = value.chars().collect();
+    let mut output = String::new();
+    let mut index = 0;
+    let mut command = ' ';
+    let mut argument_index = 0;
+    // A flag is always one character, so nothing after it can run into it.
+    let mut after_flag = false;
+
+    while index < characters.len() {
+        let character = characters[index];
+
+        if character.is_ascii_alphabetic() && !matches!(character, 'e' | 'E') {
+            command = character;
+            argument_index = 0;
+            output.push(character);
+            index += 1;
+            continue;
+        }
+
+        // Arcs take seven arguments: rx ry rotation large-arc sweep x y.
+        let is_arc_flag = matches!(command, 'a' | 'A') && matches!(argument_index % 7, 3 | 4);
+
+        if is_arc_flag && matches!(character, '0' | '1') {
+            if after_flag {
+                output.push(character);
+            } else {
+                push_number(&mut output, &character.to_string());
+            }
+            after_flag = true;
+            argument_index += 1;
+            index += 1;
+        } else if starts_number(&characters, index) {
+            let number = round_number(&read_number(&characters, &mut index));
+            if after_flag {
+                output.push_str(&number);
+            } else {
+                push_number(&mut output, &number);
+            }
+            after_flag = false;
+            argument_index += 1;
+        } else {
+            after_flag = false;
+            output.push(character);
+            index += 1;
+        }
+    }
+
+    output
+}
+
+fn starts_number(characters: &[char], index: usize) -> bool {
+    let character = characters[index];
+    let next_starts_digits = characters
+        .get(index + 1)
+        .is_some_and(|next| next.is_ascii_digit() || *next == '.');
+
+    character.is_ascii_digit() || (character == '.' || character == '-') && next_starts_digits
+}
+
+fn read_number(characters: &[char], index: &mut usize) -> String {
+    let start = *index;
+    if characters[*index] == '-' {
+        *index += 1;
+    }
+    let mut seen_decimal_point = false;
+    while let Some(&character) = characters.get(*index) {
+        if character.is_ascii_digit() {
+            *index += 1;
+        } else if character == '.' && !seen_decimal_point {
+            seen_decimal_point = true;
+            *index += 1;
+        } else {
+            break;
+        }
+    }
+    // Exponents, like 1e-5.
+    if matches!(characters.get(*index), Some('e') | Some('E'))
+        && characters
+            .get(*index + 1)
+            .is_some_and(|next| next.is_ascii_digit() || *next == '-')
+    {
+        *index += 2;
+        while characters.get(*index).is_some_and(char::is_ascii_digit) {
+            *index += 1;
+        }
+    }
+
+    characters[start..*index].iter().collect()
+}
+
+fn round_number(number: &str) -> String {
+    match number.parse::<f64>() {
+        Ok(parsed) => format_number(parsed),
+        Err(_) => number.to_string(),
+    }
+}
+
+// Rounding can change where one number ends and the next begins. "1.5-0.0001"
+// would become "1.50", and ".0001.5" would become "0.5", so we add a space
+// when the new number would otherwise run into the one before it.
+fn push_number(output: &mut String, number: &str) {
+    let previous_number: String = output
+        .chars()
+        .rev()
+        .take_while(|character| character.is_ascii_digit() || *character == '.')
+        .collect();
+
+    let runs_together = !previous_number.is_empty()
+        && match number.chars().next() {
+            Some('.') => !previous_number.contains('.'),
+            Some(character) => character.is_ascii_digit(),
+            None => false,
+        };
+
+    if runs_together {
+        output.push(' ');
+    }
+    output.push_str(number);
+}
+
+fn format_number(number: f64) -> String {
+    let formatted = format!("{:.*}", DECIMAL_PLACES, number);
+    let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    if formatted == "-0" {
+        "0".to_string()
+    } else {
+        formatted.to_string()
+    }
+}
+
+fn escape(text: &str, is_attribute: bool) -> String {
+    let escaped = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+
+    if is_attribute {
+        escaped.replace('"', "&quot;")
+    } else {
+        escaped
+    }
+}
+
+struct IncludeSvgInput {
+    path_to_input_file: PathBuf,
+    url_path: PathBuf,
+    performance_budget: Duration,
+    debug: bool,
+    span: proc_macro2::Span,
+}
+
+impl Parse for IncludeSvgInput {
+    fn parse(input: ParseStream) -> SynResult<Self> {
+        let input_span = input.span();
+
+        let error_message = r#"Please make sure to pass arguments to include_svg! like this:
+
+include_svg!(
+    path_to_input_file: \"src/assets/images/logo.svg\",
+    url_path: \"built-assets/logo.svg\",
+    performance_budget_millis: 150,
+    debug: true,
+);
+"#;
+
+        let error = syn::Error::new(input_span, error_message);
+
+        let path_to_input_file_string =
+            parse_named_string_argument("path_to_input_file", &input).ok_or(error.clone())?;
+
+        let path_to_input_file = PathBuf::from_str(&path_to_input_file_string)
+            .expect("Error parsing path_to_input_file.");
+
+        let url_path_string = parse_url_path_argument("url_path", &input)
+            .map_err(|err| err.into_syn_error(input_span))?;
+        let url_path = PathBuf::from_str(&url_path_string).expect("Error parsing url_path.");
+
+        let performance_budget_millis =
+            parse_named_u64_argument("performance_budget_millis", &input).ok_or(error.clone())?;
+        let performance_budget = Duration::from_millis(performance_budget_millis);
+
+        let debug = parse_named_bool_argument("debug", &input).unwrap_or(false);
+
+        Ok(IncludeSvgInput {
+            path_to_input_file,
+            url_path,
+            performance_budget,
+            debug,
+            span: input_span,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Like what Inkscape saves by default, as opposed to a plain SVG export.
+    static INKSCAPE_DOCUMENT: &str = r##"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<!-- Created with Inkscape (http://www.inkscape.org/) -->
+<svg
+   width="24"
+   height="24"
+   viewBox="0 0 24.000001 24"
+   version="1.1"
+   id="svg5"
+   inkscape:version="1.3 (0e150ed6c4, 2023-07-21)"
+   sodipodi:docname="icon.svg"
+   xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape"
+   xmlns:sodipodi="http://sodipodi.sourceforge.net/DTD/sodipodi-0.dtd"
+   xmlns:xlink="http://www.w3.org/1999/xlink"
+   xmlns="http://www.w3.org/2000/svg"
+   xmlns:svg="http://www.w3.org/2000/svg">
+  <title>Icon</title>
+  <sodipodi:namedview
+     id="namedview7"
+     pagecolor="#ffffff"
+     inkscape:zoom="22.627417" />
+  <defs
+     id="defs2">
+    <circle id="dot" cx="0" cy="0" r="1.0000001" />
+  </defs>
+  <g
+     inkscape:label="Layer 1"
+     inkscape:groupmode="layer">
+    <g>
+      <path
+         d="M 2.0000001,12.499999 L 21.999999,12.499999"
+         inkscape:connector-curvature="0" />
+    </g>
+    <g transform="translate(12.0004 6)">
+      <use xlink:href="#dot" />
+    </g>
+  </g>
+</svg>
+"##;
+
+    fn optimize(text: &str) -> Result<SvgAsset, String> {
+        let path = std::env::temp_dir().join(format!(
+            "optimize-svg-test-{}-{:x}.svg",
+            std::process::id(),
+            text.len()
+        ));
+        std::fs::write(&path, text).unwrap();
+        let result = optimize_svg(
+            &path,
+            PathBuf::from("built-assets/icon.svg"),
+            Duration::from_millis(100),
+        );
+        std::fs::remove_file(&path).unwrap();
+        result
+    }
+
+    #[test]
+    fn optimizes_an_inkscape_document() {
+        let svg_asset = optimize(INKSCAPE_DOCUMENT).unwrap();
+
+        assert_eq!(svg_asset.view_box, "0 0 24 24");
+        assert_eq!(
+            svg_asset.root_attributes,
+            vec![("id".to_string(), "svg5".to_string())]
+        );
+        // The layer and the group without attributes are collapsed, and
+        // Inkscape's elements and attributes, the title, and the comment
+        // are gone.
+        assert_eq!(
+            svg_asset.inner_markup,
+            r##"<defs id="defs2"><circle id="dot" cx="0" cy="0" r="1"/></defs><path d="M 2,12.5 L 22,12.5"/><g transform="translate(12 6)"><use href="#dot"/></g>"##
+        );
+    }
+
+    #[test]
+    fn requires_a_view_box() {
+        let error = optimize(r#"<svg xmlns="http://www.w3.org/2000/svg" width="24px" height="16px"><path d="M0 0L1 1"/></svg>"#)
+            .err()
+            .expect("An SVG without a viewBox should be an error.");
+
+        assert!(error.contains("needs a viewBox"));
+        assert!(error.contains(r#"viewBox="0 0 24 16""#));
+    }
+
+    #[test]
+    fn rounds_path_numbers() {
+        assert_eq!(round_path_data("M1.23456.5L-0.00001 2"), "M1.235 0.5L0 2");
+    }
+
+    #[test]
+    fn keeps_compact_arc_flags() {
+        assert_eq!(round_path_data("a10 10 0 0110 10"), "a10 10 0 0110 10");
+        assert_eq!(
+            round_path_data("M0 0A5.00001 5 0 1 0 10.5 10a2 2 0 0010.25.5"),
+            "M0 0A5 5 0 1 0 10.5 10a2 2 0 0010.25 0.5"
+        );
+    }
+
+    #[test]
+    fn keeps_numbers_apart_after_rounding() {
+        assert_eq!(round_numbers("1.5-0.0001"), "1.5 0");
+        assert_eq!(round_numbers(".0001.5"), "0 0.5");
+    }
+}
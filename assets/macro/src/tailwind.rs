@@ -3,7 +3,10 @@ use assets_runtime::{paths::*, CssAsset};
 use proc_macro::TokenStream;
 use quote::quote;
 use std::str::FromStr;
-use std::{path::PathBuf, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use syn::{
     parse::{Parse, ParseStream},
     Result as SynResult,
@@ -15,27 +18,58 @@ pub fn include(input: TokenStream) -> TokenStream {
 
     log::info!("Including Tailwind.");
 
+    let input_file = workspace_root_dir().join(&input.path_to_input_file);
+    let output_file = output_file_path(&input.url_path);
+
+    if let Err(error_message) = run(&input_file, &output_file, input.minify) {
+        return syn::Error::new(input.span, error_message)
+            .to_compile_error()
+            .into();
+    }
+
+    log::info!("Successfully built Tailwind.");
+    crate::stale_built_assets::register_output(&output_file);
+
+    let built_css = std::fs::read_to_string(output_file).expect("Error reading built.css file.");
+
+    let gzipped_size_in_bytes = crate::compression::gzipped_size_in_bytes(built_css.as_bytes());
+    let css_asset = CssAsset::new(
+        input.url_path,
+        built_css,
+        input.performance_budget,
+        input.critical_css_performance_budget,
+        gzipped_size_in_bytes,
+    );
+
+    let output = quote! {
+        #css_asset
+    };
+
+    output.into()
+}
+
+// Runs the Tailwind CLI on an input CSS file, returning an error
+// message suitable for showing as a compile error if it fails.
+pub fn run(input_file: &Path, output_file: &Path, minify: bool) -> Result<(), String> {
     let config = tailwind_config_path();
     let config_str = config
         .to_str()
         .expect("Error converting the path to the Tailwind config to a string.");
     log::info!("Using Tailwind config at {}.", config_str);
 
-    let input_file = workspace_root_dir().join(&input.path_to_input_file);
     let input_file_str = input_file
         .to_str()
         .expect("Error converting the path to the Tailwind input CSS file to a string.");
     log::info!("Using Tailwind input CSS file at {}.", input_file_str);
 
     if !input_file.exists() {
-        let error_message = format!("File not found: {}", input_file_str);
-
-        return syn::Error::new(input.span, error_message)
-            .to_compile_error()
-            .into();
+        return Err(format!("File not found: {}", input_file_str));
     }
 
-    let output_file = output_file_path(&input.url_path);
+    // The Tailwind config reads our theme from this file, so it
+    // has to be up to date before we run Tailwind.
+    write_theme().map_err(|error| format!("Error writing the Tailwind theme: {}", error))?;
+
     let output_file_str = output_file
         .to_str()
         .expect("Error converting the path to the Tailwind output CSS file to a string.");
@@ -50,32 +84,26 @@ pub fn include(input: TokenStream) -> TokenStream {
         output_file_str,
     ];
 
-    if input.minify {
+    if minify {
         tailwind_args.push("--minify");
     }
 
     log::info!("Invoking Tailwind CLI.");
-    let tailwind_cli_output = tailwind_cli::run(tailwind_args);
-
-    if let Err(error) = tailwind_cli_output {
-        let error_message = format!("Error including Tailwind:\n{}", error);
-        log::error!("{}", error_message);
-        return syn::Error::new(input.span, error_message)
-            .to_compile_error()
-            .into();
-    }
-
-    log::info!("Successfully built Tailwind.");
-
-    let built_css = std::fs::read_to_string(output_file).expect("Error reading built.css file.");
+    tailwind_cli::run(tailwind_args)
+        .map(|_| ())
+        .map_err(|error| {
+            let error_message = format!("Error including Tailwind:\n{}", error);
+            log::error!("{}", error_message);
+            error_message
+        })
+}
 
-    let css_asset = CssAsset::new(input.url_path, built_css, input.performance_budget);
+fn write_theme() -> std::io::Result<()> {
+    let theme_path = tailwind_theme_path();
+    log::info!("Writing Tailwind theme to {}.", theme_path.display());
 
-    let output = quote! {
-        #css_asset
-    };
-
-    output.into()
+    std::fs::create_dir_all(target_dir())?;
+    std::fs::write(theme_path, shared::design_tokens::tailwind_theme_json())
 }
 
 pub fn tailwind_config_path() -> PathBuf {
@@ -86,6 +114,7 @@ struct IncludeTailwindInput {
     path_to_input_file: PathBuf,
     url_path: PathBuf,
     performance_budget: Duration,
+    critical_css_performance_budget: Duration,
     minify: bool,
     debug: bool,
     span: proc_macro2::Span,
@@ -101,6 +130,7 @@ include_tailwind!(
     path_to_input_file: \"src/main.css\",
     url_path: \"built.css\",
     performance_budget_millis: 300,
+    critical_css_performance_budget_millis: 150,
     minify: true,
     debug: true,
 );
@@ -122,6 +152,13 @@ include_tailwind!(
             parse_named_u64_argument("performance_budget_millis", &input).ok_or(error.clone())?;
         let performance_budget = Duration::from_millis(performance_budget_millis);
 
+        // For the rules from this stylesheet that the server inlines in each page.
+        let critical_css_performance_budget_millis =
+            parse_named_u64_argument("critical_css_performance_budget_millis", &input)
+                .ok_or(error.clone())?;
+        let critical_css_performance_budget =
+            Duration::from_millis(critical_css_performance_budget_millis);
+
         // True if we're in release mode (i.e. `cargo build --release`).
         let release_mode = !cfg!(debug_assertions);
         // Default to minifying in release mode.
@@ -134,6 +171,7 @@ include_tailwind!(
             path_to_input_file,
             url_path,
             performance_budget,
+            critical_css_performance_budget,
             minify,
             debug,
             span: input_span,
@@ -0,0 +1,342 @@
+use assets_runtime::paths::*;
+use proc_macro::TokenStream;
+use quote::quote;
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use syn::LitStr;
+use walkdir::WalkDir;
+
+// Every class name in the CSS Tailwind generated, and when the newest file
+// Tailwind read was last modified. Proc macros can stay loaded for a long
+// time, like in rust-analyzer, so we run Tailwind again when that changes.
+static CLASS_NAMES: Mutex<Option<(SystemTime, Arc<BTreeSet<String>>)>> = Mutex::new(None);
+
+// Checks that every class in a string literal is in the CSS Tailwind
+// generates, and expands to the literal itself. Tailwind scans our
+// source files for class names, so if a class isn't in its output,
+// it's a typo or a class Tailwind doesn't know about.
+pub fn check(input: TokenStream) -> TokenStream {
+    let classes = syn::parse_macro_input!(input as LitStr);
+    crate::logger::init_logger(false);
+
+    let class_names = match class_names() {
+        Ok(class_names) => class_names,
+        Err(error_message) => {
+            return syn::Error::new(classes.span(), error_message)
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let allowed_classes = &crate::config::config().tailwind.allowed_classes;
+    let value = classes.value();
+    let unknown_classes: Vec<&str> = value
+        .split_whitespace()
+        .filter(|class| !class_names.contains(*class))
+        .filter(|class| !allowed_classes.iter().any(|allowed| allowed == class))
+        .collect();
+
+    if !unknown_classes.is_empty() {
+        let descriptions: Vec<String> = unknown_classes
+            .iter()
+            .map(|class| match closest_class_name(class, &class_names) {
+                Some(suggestion) => format!("`{}` (did you mean `{}`?)", class, suggestion),
+                None => format!("`{}`", class),
+            })
+            .collect();
+
+        let error_message = format!(
+            "Tailwind didn't generate any CSS for {}. If this is on purpose, add it to allowed_classes in [tailwind] in assets.toml.",
+            descriptions.join(", ")
+        );
+
+        return syn::Error::new(classes.span(), error_message)
+            .to_compile_error()
+            .into();
+    }
+
+    let output = quote! {
+        #classes
+    };
+
+    output.into()
+}
+
+fn class_names() -> Result<Arc<BTreeSet<String>>, String> {
+    let last_modified = tailwind_inputs_last_modified();
+
+    let mut cached = CLASS_NAMES
+        .lock()
+        .expect("Error locking the Tailwind class names.");
+    if let Some((cached_last_modified, class_names)) = &*cached {
+        if *cached_last_modified == last_modified {
+            return Ok(class_names.clone());
+        }
+    }
+
+    let path_to_input_file = crate::config::config()
+        .tailwind
+        .path_to_input_file
+        .as_ref()
+        .ok_or("class! needs to know which CSS file to run Tailwind on. Please add it to assets.toml like this:
+
+[tailwind]
+path_to_input_file = \"server/src/assets/main.css\"
+")?;
+
+    log::info!("Building Tailwind to check class names.");
+    let input_file = workspace_root_dir().join(path_to_input_file);
+    let output_file = tailwind_class_check_css_path();
+    crate::tailwind::run(&input_file, &output_file, false)?;
+
+    let css = std::fs::read_to_string(&output_file)
+        .map_err(|error| format!("Error reading {}: {}", output_file.display(), error))?;
+
+    let class_names = Arc::new(class_names_from_css(&css));
+    write_class_names_file(&class_names);
+    *cached = Some((last_modified, class_names.clone()));
+
+    Ok(class_names)
+}
+
+// Tailwind scans every HTML, Rust, and CSS file in the workspace for class
+// names. See `content` in tailwind.config.js.
+fn tailwind_inputs_last_modified() -> SystemTime {
+    let is_tailwind_input = |path: &std::path::Path| {
+        path.extension()
+            .is_some_and(|extension| extension == "html" || extension == "rs" || extension == "css")
+    };
+
+    WalkDir::new(workspace_root_dir())
+        .into_iter()
+        .filter_entry(|entry| {
+            let file_name = entry.file_name().to_string_lossy();
+            let is_ignored_dir =
+                file_name.starts_with('.') || file_name == "target" || file_name == "node_modules";
+            !is_ignored_dir
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| is_tailwind_input(entry.path()))
+        .map(|entry| entry.into_path())
+        .chain([crate::tailwind::tailwind_config_path()])
+        .filter_map(|path| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .max()
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+fn write_class_names_file(class_names: &BTreeSet<String>) {
+    let contents: Vec<&str> = class_names.iter().map(String::as_str).collect();
+    let path = tailwind_class_names_path();
+    if let Err(error) = std::fs::write(&path, contents.join("\n")) {
+        log::warn!("Error writing {}: {}", path.display(), error);
+    }
+}
+
+// The class names used in the selectors of a stylesheet, ignoring
+// declarations, at-rules like @font-face and @keyframes, and strings.
+fn class_names_from_css(css: &str) -> BTreeSet<String> {
+    let characters: Vec<char> = css.chars().collect();
+    let mut class_names = BTreeSet::new();
+    let mut index = 0;
+    let mut prelude_start = 0;
+
+    while let Some(&character) = characters.get(index) {
+        match character {
+            '/' if characters.get(index + 1) == Some(&'*') => {
+                index += 2;
+                while index < characters.len()
+                    && !(characters[index] == '*' && characters.get(index + 1) == Some(&'/'))
+                {
+                    index += 1;
+                }
+                index += 2;
+            }
+            '"' | '\'' => index = skip_string(&characters, index),
+            // Escaped characters in selectors, like the quotes in content-['hi'].
+            '\\' => index += 2,
+            ';' | '}' => {
+                index += 1;
+                prelude_start = index;
+            }
+            '{' => {
+                let prelude: String = characters[prelude_start..index].iter().collect();
+                let prelude = prelude.trim();
+
+                if is_conditional_group(prelude) {
+                    // Keep going inside the block, which contains more rules.
+                    index += 1;
+                } else {
+                    if !prelude.starts_with('@') {
+                        class_names.extend(class_names_in_selector(prelude));
+                    }
+                    index = skip_block(&characters, index);
+                }
+
+                prelude_start = index;
+            }
+            _ => index += 1,
+        }
+    }
+
+    class_names
+}
+
+fn class_names_in_selector(selector: &str) -> Vec<String> {
+    let characters: Vec<char> = selector.chars().collect();
+    let mut class_names = vec![];
+    let mut index = 0;
+
+    while let Some(&character) = characters.get(index) {
+        match character {
+            '.' => {
+                index += 1;
+                let class_name = read_identifier(&characters, &mut index);
+                if !class_name.is_empty() {
+                    class_names.push(class_name);
+                }
+            }
+            // Attribute selectors can contain dots, like [href$=".pdf"].
+            '[' => {
+                while index < characters.len() && characters[index] != ']' {
+                    if matches!(characters[index], '"' | '\'') {
+                        index = skip_string(&characters, index);
+                    } else {
+                        index += 1;
+                    }
+                }
+            }
+            '\\' => index += 2,
+            _ => index += 1,
+        }
+    }
+
+    class_names
+}
+
+// Reads a CSS identifier, unescaping things like `\:` and `\[` in
+// `md\:w-grid-52` and `scale-\[80\%\]`, and hex escapes like `\32 xl`.
+fn read_identifier(characters: &[char], index: &mut usize) -> String {
+    let mut identifier = String::new();
+
+    while let Some(&character) = characters.get(*index) {
+        if character == '\\' {
+            *index += 1;
+            let hex_digits: String = characters[*index..]
+                .iter()
+                .take(6)
+                .take_while(|character| character.is_ascii_hexdigit())
+                .collect();
+
+            if hex_digits.is_empty() {
+                if let Some(&escaped) = characters.get(*index) {
+                    identifier.push(escaped);
+                }
+                *index += 1;
+            } else {
+                *index += hex_digits.len();
+                if let Some(unescaped) = u32::from_str_radix(&hex_digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                {
+                    identifier.push(unescaped);
+                }
+                // A single whitespace character ends a hex escape.
+                if characters.get(*index).is_some_and(|c| c.is_whitespace()) {
+                    *index += 1;
+                }
+            }
+        } else if character.is_alphanumeric() || character == '-' || character == '_' {
+            identifier.push(character);
+            *index += 1;
+        } else {
+            break;
+        }
+    }
+
+    identifier
+}
+
+fn skip_string(characters: &[char], start: usize) -> usize {
+    let quote = characters[start];
+    let mut index = start + 1;
+
+    while index < characters.len() && characters[index] != quote {
+        if characters[index] == '\\' {
+            index += 1;
+        }
+        index += 1;
+    }
+
+    index + 1
+}
+
+// Returns the index just past the brace that closes the block starting at `start`.
+fn skip_block(characters: &[char], start: usize) -> usize {
+    let mut depth = 0;
+    let mut index = start;
+
+    while let Some(&character) = characters.get(index) {
+        match character {
+            '"' | '\'' => {
+                index = skip_string(characters, index);
+                continue;
+            }
+            '\\' => index += 1,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return index + 1;
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    index
+}
+
+// At-rules that contain other rules, which can have class names in them.
+fn is_conditional_group(prelude: &str) -> bool {
+    ["@media", "@supports", "@container", "@layer"]
+        .iter()
+        .any(|at_rule| prelude.starts_with(at_rule))
+}
+
+// Suggests a class name that's only a couple of typos away.
+fn closest_class_name<'a>(class: &str, class_names: &'a BTreeSet<String>) -> Option<&'a str> {
+    let max_distance = 2;
+
+    class_names
+        .iter()
+        .map(|class_name| (edit_distance(class, class_name), class_name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, class_name)| class_name.as_str())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_character) in a.chars().enumerate() {
+        let mut current_row = vec![i + 1];
+        for (j, b_character) in b.iter().enumerate() {
+            let substitution_cost = if a_character == *b_character { 0 } else { 1 };
+            current_row.push(
+                (previous_row[j] + substitution_cost)
+                    .min(previous_row[j + 1] + 1)
+                    .min(current_row[j] + 1),
+            );
+        }
+        previous_row = current_row;
+    }
+
+    previous_row[b.len()]
+}
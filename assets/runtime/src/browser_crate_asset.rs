@@ -1,9 +1,35 @@
-use crate::{JsAsset, WasmAsset};
+use crate::{JsAsset, ResourceHint, WasmAsset};
 use cfg_if::cfg_if;
 
 pub struct BrowserCrateAsset {
     pub wasm: WasmAsset,
     pub js: JsAsset,
+    // Critical browser crates are needed as soon as the page loads,
+    // so their JS and wasm get preloaded.
+    pub critical: bool,
+}
+
+impl BrowserCrateAsset {
+    pub fn resource_hints(&self) -> Vec<ResourceHint> {
+        if !self.critical {
+            return vec![];
+        }
+
+        // Inlined JS is already in the page, so there's nothing to preload.
+        let js_preload = (!self.js.should_inline()).then(|| ResourceHint::ModulePreload {
+            href: self.js.url_path.to_string_lossy().to_string(),
+        });
+
+        // wasm-bindgen loads the wasm with fetch(), so we preload it as a fetch.
+        let wasm_preload = ResourceHint::Preload {
+            href: self.wasm.url_path.to_string_lossy().to_string(),
+            destination: "fetch",
+            mime_type: "application/wasm",
+            crossorigin: true,
+        };
+
+        js_preload.into_iter().chain([wasm_preload]).collect()
+    }
 }
 
 cfg_if! {
@@ -18,11 +44,13 @@ if #[cfg(feature = "build_time")] {
 
             let wasm = &self.wasm;
             let js = &self.js;
+            let critical = self.critical;
 
             let quoted = quote! {
                 assets::BrowserCrateAsset {
                     wasm: #wasm,
                     js: #js,
+                    critical: #critical,
                 }
             };
 
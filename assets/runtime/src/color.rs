@@ -0,0 +1,70 @@
+use cfg_if::cfg_if;
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Color {
+    pub fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    // Relative luminance as defined by WCAG, from 0 for black to 1 for white.
+    pub fn relative_luminance(&self) -> f32 {
+        let linearize = |channel: u8| {
+            let channel = channel as f32 / 255.0;
+            if channel <= 0.03928 {
+                channel / 12.92
+            } else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        0.2126 * linearize(self.red)
+            + 0.7152 * linearize(self.green)
+            + 0.0722 * linearize(self.blue)
+    }
+
+    // Whether white text is easier to read on this color than black text.
+    pub fn is_dark(&self) -> bool {
+        // This is the luminance where white and black text
+        // have the same contrast ratio.
+        self.relative_luminance() < 0.179
+    }
+}
+
+// Renders a CSS color like "rgb(255, 255, 255)".
+impl Display for Color {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "rgb({}, {}, {})", self.red, self.green, self.blue)
+    }
+}
+
+cfg_if! {
+if #[cfg(feature = "build_time")] {
+
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+
+    impl ToTokens for Color {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let red = self.red;
+            let green = self.green;
+            let blue = self.blue;
+
+            tokens.extend(quote! {
+                assets::Color {
+                    red: #red,
+                    green: #green,
+                    blue: #blue,
+                }
+            });
+        }
+    }
+
+}
+}
@@ -1,4 +1,4 @@
-use crate::built_assets_browser_prefix;
+use crate::{built_assets_browser_prefix, ResourceHint};
 use cfg_if::cfg_if;
 use std::{path::PathBuf, time::Duration};
 
@@ -8,10 +8,20 @@ pub struct CssAsset {
     pub url_path_starting_from_built_assets_dir: PathBuf, // Used for saving the asset to disk.
     pub contents: String,
     pub load_time_budget: Duration,
+    // For the rules we inline in each page. See `critical_css` in the server.
+    pub critical_css_load_time_budget: Duration,
+    // For deciding whether to inline the contents. See `should_inline`.
+    pub gzipped_size_in_bytes: usize,
 }
 
 impl CssAsset {
-    pub fn new(url_path: PathBuf, contents: String, load_time_budget: Duration) -> Self {
+    pub fn new(
+        url_path: PathBuf,
+        contents: String,
+        load_time_budget: Duration,
+        critical_css_load_time_budget: Duration,
+        gzipped_size_in_bytes: usize,
+    ) -> Self {
         let url_path_starting_from_built_assets_dir = url_path
             .strip_prefix(built_assets_browser_prefix())
             .expect("Error stripping prefix.")
@@ -22,6 +32,8 @@ impl CssAsset {
             url_path_starting_from_built_assets_dir,
             contents,
             load_time_budget,
+            critical_css_load_time_budget,
+            gzipped_size_in_bytes,
         };
 
         #[cfg(feature = "build_time")]
@@ -29,6 +41,19 @@ impl CssAsset {
 
         asset
     }
+
+    pub fn should_inline(&self) -> bool {
+        self.gzipped_size_in_bytes < crate::inline_threshold_gzipped_bytes
+    }
+
+    pub fn resource_hint(&self) -> ResourceHint {
+        ResourceHint::Preload {
+            href: self.url_path.to_string_lossy().to_string(),
+            destination: "style",
+            mime_type: "text/css",
+            crossorigin: false,
+        }
+    }
 }
 
 cfg_if! {
@@ -73,12 +98,19 @@ if #[cfg(feature = "build_time")] {
             let load_time_budget_millis = self.load_time_budget.as_millis() as u64;
             // log::info!("load_time_budget_millis: {}", load_time_budget_millis);
 
+            let critical_css_load_time_budget_millis =
+                self.critical_css_load_time_budget.as_millis() as u64;
+
+            let gzipped_size_in_bytes = self.gzipped_size_in_bytes;
+
             let quoted = quote! {
                 assets::CssAsset {
                     url_path: std::path::PathBuf::from(#url_path),
                     url_path_starting_from_built_assets_dir: std::path::PathBuf::from(#url_path_starting_from_built_assets_dir),
                     contents: #contents.to_string(),
                     load_time_budget: std::time::Duration::from_millis(#load_time_budget_millis),
+                    critical_css_load_time_budget: std::time::Duration::from_millis(#critical_css_load_time_budget_millis),
+                    gzipped_size_in_bytes: #gzipped_size_in_bytes,
                 }
             };
 
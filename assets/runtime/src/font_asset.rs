@@ -1,6 +1,6 @@
-use crate::built_assets_browser_prefix;
+use crate::{built_assets_browser_prefix, ResourceHint};
 use cfg_if::cfg_if;
-use std::{path::PathBuf, time::Duration};
+use std::{fmt::Display, path::PathBuf, time::Duration};
 
 #[derive(PartialEq)]
 pub struct FontAsset {
@@ -8,10 +8,64 @@ pub struct FontAsset {
     pub url_path_starting_from_built_assets_dir: PathBuf, // Used for saving the asset to disk.
     pub load_time_budget: Duration,
     pub size_in_bytes: usize, // For checking performance budgets.
+    pub face: FontFace,       // For generating the @font-face rule.
+    // Critical fonts are used above the fold, so they get preloaded.
+    pub critical: bool,
+}
+
+/// The descriptors that go in a font's `@font-face` rule.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontFace {
+    pub family: String,
+    pub weight: u16,
+    pub style: FontStyle,
+    pub display: FontDisplay,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FontStyle {
+    Normal,
+    Italic,
+}
+
+impl Display for FontStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FontStyle::Normal => write!(f, "normal"),
+            FontStyle::Italic => write!(f, "italic"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FontDisplay {
+    Auto,
+    Block,
+    Swap,
+    Fallback,
+    Optional,
+}
+
+impl Display for FontDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FontDisplay::Auto => write!(f, "auto"),
+            FontDisplay::Block => write!(f, "block"),
+            FontDisplay::Swap => write!(f, "swap"),
+            FontDisplay::Fallback => write!(f, "fallback"),
+            FontDisplay::Optional => write!(f, "optional"),
+        }
+    }
 }
 
 impl FontAsset {
-    pub fn new(url_path: PathBuf, load_time_budget: Duration, size_in_bytes: usize) -> Self {
+    pub fn new(
+        url_path: PathBuf,
+        load_time_budget: Duration,
+        size_in_bytes: usize,
+        face: FontFace,
+        critical: bool,
+    ) -> Self {
         let url_path_starting_from_built_assets_dir = url_path
             .strip_prefix(built_assets_browser_prefix())
             .expect("Error stripping prefix.")
@@ -22,6 +76,8 @@ impl FontAsset {
             url_path_starting_from_built_assets_dir,
             load_time_budget,
             size_in_bytes,
+            face,
+            critical,
         };
 
         #[cfg(feature = "build_time")]
@@ -29,6 +85,51 @@ impl FontAsset {
 
         asset
     }
+
+    // The value for the format() hint in @font-face, so browsers
+    // can skip formats they don't support without downloading them.
+    pub fn format(&self) -> &'static str {
+        match self
+            .url_path
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("woff2") => "woff2",
+            Some("woff") => "woff",
+            Some("otf") => "opentype",
+            _ => "truetype",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self.format() {
+            "woff2" => "font/woff2",
+            "woff" => "font/woff",
+            "opentype" => "font/otf",
+            _ => "font/ttf",
+        }
+    }
+
+    pub fn resource_hint(&self) -> Option<ResourceHint> {
+        self.critical.then(|| ResourceHint::Preload {
+            href: self.url_path.to_string_lossy().to_string(),
+            destination: "font",
+            mime_type: self.mime_type(),
+            crossorigin: true,
+        })
+    }
+
+    pub fn to_font_face_rule(&self) -> String {
+        format!(
+            "@font-face{{font-family:'{}';src:url('{}') format('{}');font-weight:{};font-style:{};font-display:{}}}",
+            self.face.family,
+            self.url_path.to_string_lossy(),
+            self.format(),
+            self.face.weight,
+            self.face.style,
+            self.face.display
+        )
+    }
 }
 
 cfg_if! {
@@ -74,12 +175,17 @@ if #[cfg(feature = "build_time")] {
             let size_in_bytes = self.size_in_bytes;
             // log::info!("size_in_bytes: {}", size_in_bytes);
 
+            let face = &self.face;
+            let critical = self.critical;
+
             let quoted = quote! {
                 assets::FontAsset {
                     url_path: std::path::PathBuf::from(#url_path),
                     url_path_starting_from_built_assets_dir: std::path::PathBuf::from(#url_path_starting_from_built_assets_dir),
                     load_time_budget: std::time::Duration::from_millis(#load_time_budget_millis),
                     size_in_bytes: #size_in_bytes,
+                    face: #face,
+                    critical: #critical,
                 }
             };
             // log::info!("quoted: {}", quoted);
@@ -89,5 +195,48 @@ if #[cfg(feature = "build_time")] {
         }
     }
 
+    impl ToTokens for FontFace {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let family = &self.family;
+            let weight = self.weight;
+            let style = &self.style;
+            let display = &self.display;
+
+            tokens.extend(quote! {
+                assets::FontFace {
+                    family: #family.to_string(),
+                    weight: #weight,
+                    style: #style,
+                    display: #display,
+                }
+            });
+        }
+    }
+
+    impl ToTokens for FontStyle {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let quoted = match self {
+                FontStyle::Normal => quote! { assets::FontStyle::Normal },
+                FontStyle::Italic => quote! { assets::FontStyle::Italic },
+            };
+
+            tokens.extend(quoted);
+        }
+    }
+
+    impl ToTokens for FontDisplay {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let quoted = match self {
+                FontDisplay::Auto => quote! { assets::FontDisplay::Auto },
+                FontDisplay::Block => quote! { assets::FontDisplay::Block },
+                FontDisplay::Swap => quote! { assets::FontDisplay::Swap },
+                FontDisplay::Fallback => quote! { assets::FontDisplay::Fallback },
+                FontDisplay::Optional => quote! { assets::FontDisplay::Optional },
+            };
+
+            tokens.extend(quoted);
+        }
+    }
+
 }
 }
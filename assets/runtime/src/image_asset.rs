@@ -1,3 +1,4 @@
+use crate::{Color, Sizes};
 use cfg_if::cfg_if;
 
 #[derive(Clone)]
@@ -8,14 +9,46 @@ pub struct ImageAsset {
     pub width: u32,
     pub height: u32,
 
+    // The most common colors in the image, most common first, for picking
+    // accents or text colors that go with it. The dominant color is the
+    // first one in the palette, or black if the image is fully transparent.
+    pub dominant_color: Color,
+    pub palette: Vec<Color>,
+
+    // How wide the image is displayed, for the `sizes` attribute.
+    // Components can override this for a particular use.
+    pub sizes: Sizes,
+
+    // One source per format, in order of preference, for rendering
+    // `<source>` elements inside a `<picture>`.
+    pub sources: Vec<ImageSource>,
+
+    // The fallback format, for browsers that don't support any of the sources.
     pub srcset: String,
     pub src: String,
 }
 
+#[derive(Clone)]
+pub struct ImageSource {
+    pub mime_type: String,
+    pub srcset: String,
+}
+
 #[derive(Clone)]
 pub enum Placeholder {
-    Lqip { data_uri: String },
-    Color { css_string: String },
+    Lqip {
+        data_uri: String,
+    },
+    Color {
+        css_string: String,
+    },
+    // `hash` is the base64 encoded ThumbHash, for decoding in the browser.
+    // `css_background` approximates it with gradients, for rendering on the
+    // server without any JavaScript.
+    ThumbHash {
+        hash: String,
+        css_background: String,
+    },
 }
 
 cfg_if! {
@@ -44,10 +77,33 @@ if #[cfg(feature = "build_time")] {
                         }
                     }
                 }
+                Placeholder::ThumbHash { hash, css_background } => {
+                    quote! {
+                        assets::Placeholder::ThumbHash {
+                            hash: #hash.to_string(),
+                            css_background: #css_background.to_string(),
+                        }
+                    }
+                }
             };
 
             let width = self.width;
             let height = self.height;
+            let dominant_color = &self.dominant_color;
+            let palette = &self.palette;
+            let sizes = &self.sizes;
+
+            let sources = self.sources.iter().map(|source| {
+                let mime_type = &source.mime_type;
+                let srcset = &source.srcset;
+
+                quote! {
+                    assets::ImageSource {
+                        mime_type: #mime_type.to_string(),
+                        srcset: #srcset.to_string(),
+                    }
+                }
+            });
 
             let srcset = &self.srcset;
             let src = &self.src;
@@ -60,6 +116,13 @@ if #[cfg(feature = "build_time")] {
                     width: #width,
                     height: #height,
 
+                    dominant_color: #dominant_color,
+                    palette: vec![#(#palette),*],
+
+                    sizes: #sizes,
+
+                    sources: vec![#(#sources),*],
+
                     srcset: #srcset.to_string(),
                     src: #src.to_string(),
                 }
@@ -0,0 +1,4 @@
+// CSS and JS smaller than this once gzipped are inlined into the page
+// instead of linked. Below this size, the extra round trip to fetch a
+// separate file costs more than downloading the bytes again on every page.
+pub static inline_threshold_gzipped_bytes: usize = 4 * 1024;
@@ -9,10 +9,17 @@ pub struct JsAsset {
     pub url_path_starting_from_built_assets_dir: PathBuf, // Used for saving the asset to disk.
     pub contents: String,
     pub load_time_budget: Duration,
+    // For deciding whether to inline the contents. See `should_inline`.
+    pub gzipped_size_in_bytes: usize,
 }
 
 impl JsAsset {
-    pub fn new(url_path: PathBuf, contents: String, load_time_budget: Duration) -> Self {
+    pub fn new(
+        url_path: PathBuf,
+        contents: String,
+        load_time_budget: Duration,
+        gzipped_size_in_bytes: usize,
+    ) -> Self {
         let url_path_starting_from_built_assets_dir = url_path
             .strip_prefix(built_assets_browser_prefix())
             .expect("Error stripping prefix.")
@@ -23,6 +30,7 @@ impl JsAsset {
             url_path_starting_from_built_assets_dir,
             contents,
             load_time_budget,
+            gzipped_size_in_bytes,
         };
 
         #[cfg(feature = "build_time")]
@@ -30,6 +38,19 @@ impl JsAsset {
 
         asset
     }
+
+    // Inlined modules resolve relative imports against the page instead of
+    // the built assets folder, so modules with them are always linked.
+    pub fn should_inline(&self) -> bool {
+        self.gzipped_size_in_bytes < crate::inline_threshold_gzipped_bytes
+            && !self.has_relative_imports()
+    }
+
+    fn has_relative_imports(&self) -> bool {
+        ["from '.", "from \".", "import('.", "import(\"."]
+            .iter()
+            .any(|relative_import| self.contents.contains(relative_import))
+    }
 }
 
 cfg_if! {
@@ -73,12 +94,15 @@ if #[cfg(feature = "build_time")] {
             let load_time_budget_millis = self.load_time_budget.as_millis() as u64;
             // log::info!("load_time_budget_millis: {}", load_time_budget_millis);
 
+            let gzipped_size_in_bytes = self.gzipped_size_in_bytes;
+
             let quoted = quote! {
                 assets::JsAsset {
                     url_path: std::path::PathBuf::from(#url_path),
                     url_path_starting_from_built_assets_dir: std::path::PathBuf::from(#url_path_starting_from_built_assets_dir),
                     contents: #contents.to_string(),
                     load_time_budget: std::time::Duration::from_millis(#load_time_budget_millis),
+                    gzipped_size_in_bytes: #gzipped_size_in_bytes,
                 }
             };
 
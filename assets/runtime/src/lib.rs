@@ -3,11 +3,20 @@
 mod browser_crate_asset;
 pub use self::browser_crate_asset::BrowserCrateAsset;
 
+mod color;
+pub use self::color::Color;
+
 mod css_asset;
 pub use self::css_asset::CssAsset;
 
+mod inline_threshold;
+pub use self::inline_threshold::*;
+
 mod image_asset;
-pub use self::image_asset::{ImageAsset, Placeholder};
+pub use self::image_asset::{ImageAsset, ImageSource, Placeholder};
+
+mod sizes;
+pub use self::sizes::{Sizes, SizesLength};
 
 mod light_dark_image_asset;
 pub use self::light_dark_image_asset::LightDarkImageAsset;
@@ -19,12 +28,19 @@ pub mod paths;
 pub use self::paths::*;
 
 mod performance_budget;
+pub use self::performance_budget::{HasPerformanceBudget, HowCloseToBudget};
+
+mod resource_hint;
+pub use self::resource_hint::ResourceHint;
 
 mod wasm_asset;
 pub use self::wasm_asset::WasmAsset;
 
 mod font_asset;
-pub use self::font_asset::FontAsset;
+pub use self::font_asset::{FontAsset, FontDisplay, FontFace, FontStyle};
 
 mod file_asset;
 pub use self::file_asset::FileAsset;
+
+mod svg_asset;
+pub use self::svg_asset::SvgAsset;
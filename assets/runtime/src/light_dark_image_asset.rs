@@ -29,6 +29,10 @@ pub enum LightDarkPlaceholder {
         light_mode_css_string: String,
         dark_mode_css_string: String,
     },
+    ThumbHash {
+        light_mode_css_background: String,
+        dark_mode_css_background: String,
+    },
 }
 
 impl LightDarkPlaceholder {
@@ -56,8 +60,21 @@ impl LightDarkPlaceholder {
                 light_mode_css_string: light_mode_css_string.clone(),
                 dark_mode_css_string: dark_mode_css_string.clone(),
             },
+            (
+                Placeholder::ThumbHash {
+                    css_background: light_mode_css_background,
+                    ..
+                },
+                Placeholder::ThumbHash {
+                    css_background: dark_mode_css_background,
+                    ..
+                },
+            ) => LightDarkPlaceholder::ThumbHash {
+                light_mode_css_background: light_mode_css_background.clone(),
+                dark_mode_css_background: dark_mode_css_background.clone(),
+            },
             (_, _) => panic!(
-                "When defining a light-dark image asset, all images must have the same kind of placeholder (LQIP or color)."
+                "When defining a light-dark image asset, all images must have the same kind of placeholder (LQIP, color, or ThumbHash)."
             ),
         }
     }
@@ -33,6 +33,46 @@ pub fn target_dir() -> PathBuf {
     workspace_root_dir().join("target")
 }
 
+/// Resized images, keyed by a hash of the original image and
+/// the resize parameters. See `BuildTimeResizedImage`.
+pub fn image_cache_dir() -> PathBuf {
+    target_dir().join("image_cache")
+}
+
+/// Captions generated for `alt: automatic`, keyed by a hash
+/// of the image. See `assets/macro/src/images/captioning.rs`.
+pub fn caption_cache_dir() -> PathBuf {
+    target_dir().join("caption_cache")
+}
+
+/// Font subsets generated by `include_font!`, keyed by a hash of
+/// the font and the characters kept. See `assets/macro/src/font_subset.rs`.
+pub fn font_cache_dir() -> PathBuf {
+    target_dir().join("font_cache")
+}
+
+/// The Tailwind theme generated from `shared::design_tokens`,
+/// which `assets/macro/tailwind.config.js` reads.
+pub fn tailwind_theme_path() -> PathBuf {
+    target_dir().join("tailwind_theme.json")
+}
+
+/// Every class name in the CSS Tailwind generated, one per line.
+/// Written by `include_tailwind!` and `class!` for debugging.
+pub fn tailwind_class_names_path() -> PathBuf {
+    target_dir().join("tailwind_class_names.txt")
+}
+
+/// Where `class!` builds Tailwind when it runs before `include_tailwind!`.
+pub fn tailwind_class_check_css_path() -> PathBuf {
+    target_dir().join("tailwind_class_check.css")
+}
+
+/// Project-wide settings for the asset macros.
+pub fn assets_config_path() -> PathBuf {
+    workspace_root_dir().join("assets.toml")
+}
+
 pub fn cargo_install_dir() -> PathBuf {
     target_dir().join("cargo_install")
 }
@@ -41,6 +81,16 @@ pub fn path_to_cargo_install_binary(binary_name: &str) -> PathBuf {
     cargo_install_dir().join("bin").join(binary_name)
 }
 
+/// A Python virtual environment for build tools like pyftsubset.
+/// Created by `./scripts setup`.
+pub fn python_dir() -> PathBuf {
+    target_dir().join("python")
+}
+
+pub fn path_to_python_binary(binary_name: &str) -> PathBuf {
+    python_dir().join("bin").join(binary_name)
+}
+
 /// When loading assets in the browser, URL paths should
 /// start with this prefix.
 ///
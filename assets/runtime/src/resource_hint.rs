@@ -0,0 +1,83 @@
+/// A hint that tells the browser to fetch something, or connect to an
+/// origin, before it finds out it needs it. Rendered as a `<link>` in the
+/// head, so that critical assets don't have to wait for the stylesheet
+/// or script that references them.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResourceHint {
+    // For JS modules. Unlike preload, this also parses and compiles
+    // the module ahead of time.
+    ModulePreload {
+        href: String,
+    },
+    Preload {
+        href: String,
+        // The value of the `as` attribute, like "font" or "fetch".
+        destination: &'static str,
+        mime_type: &'static str,
+        // Fonts and fetches are always requested in CORS mode, so their
+        // preloads need crossorigin, or the browser downloads them twice.
+        crossorigin: bool,
+    },
+    // For responsive images, like the one that's the largest contentful
+    // paint. The browser picks from `srcset` using `sizes`, the same way
+    // it would for the image itself.
+    ImagePreload {
+        // For browsers that don't support `imagesrcset`.
+        href: Option<String>,
+        srcset: String,
+        sizes: String,
+        // Set if `srcset` is in a format that not every browser supports,
+        // like AVIF, so that the others skip the preload.
+        mime_type: Option<String>,
+    },
+    Preconnect {
+        origin: String,
+    },
+}
+
+impl ResourceHint {
+    // The same hint as a value for a `Link` response header, so that the
+    // browser can act on it before it's received any of the HTML.
+    pub fn to_link_header_value(&self) -> String {
+        match self {
+            ResourceHint::ModulePreload { href } => format!("<{}>; rel=modulepreload", href),
+            ResourceHint::Preload {
+                href,
+                destination,
+                mime_type,
+                crossorigin,
+            } => {
+                let crossorigin = if *crossorigin { "; crossorigin" } else { "" };
+                format!(
+                    "<{}>; rel=preload; as={}; type=\"{}\"{}",
+                    href, destination, mime_type, crossorigin
+                )
+            }
+            ResourceHint::ImagePreload {
+                href,
+                srcset,
+                sizes,
+                mime_type,
+            } => {
+                // Link headers need a URL, so without an href we use the
+                // first candidate in the srcset, which is ignored anyway by
+                // browsers that support imagesrcset.
+                let href = href
+                    .as_deref()
+                    .or_else(|| srcset.split_whitespace().next())
+                    .unwrap_or_default();
+                let mime_type = mime_type
+                    .as_ref()
+                    .map(|mime_type| format!("; type=\"{}\"", mime_type))
+                    .unwrap_or_default();
+                format!(
+                    "<{}>; rel=preload; as=image; imagesrcset=\"{}\"; imagesizes=\"{}\"{}; fetchpriority=high",
+                    href, srcset, sizes, mime_type
+                )
+            }
+            ResourceHint::Preconnect { origin } => {
+                format!("<{}>; rel=preconnect; crossorigin", origin)
+            }
+        }
+    }
+}
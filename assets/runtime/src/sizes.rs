@@ -0,0 +1,366 @@
+use cfg_if::cfg_if;
+use shared::design_tokens::grid_columns;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+// Describes how wide an image will be displayed, for the `sizes`
+// attribute. Without it, browsers assume the image spans the whole
+// viewport and download a much larger copy than they need.
+//
+// Each condition is a media query and the width to use when it matches.
+// Browsers use the first matching condition, and otherwise the default.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sizes {
+    pub conditions: Vec<(String, SizesLength)>,
+    pub default: SizesLength,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SizesLength {
+    Vw(f32),
+    Px(u32),
+    // CSS comparison functions, like min(100vw, 640px).
+    Min(Vec<SizesLength>),
+    Max(Vec<SizesLength>),
+    Clamp(Box<[SizesLength; 3]>),
+}
+
+impl Sizes {
+    pub fn vw(vw: f32) -> Self {
+        Self {
+            conditions: vec![],
+            default: SizesLength::Vw(vw),
+        }
+    }
+
+    pub fn px(px: u32) -> Self {
+        Self {
+            conditions: vec![],
+            default: SizesLength::Px(px),
+        }
+    }
+
+    // Matches our `grid-N` Tailwind units. See `shared::design_tokens::grid`.
+    pub fn grid(columns: u32) -> Self {
+        Self {
+            conditions: vec![],
+            default: SizesLength::grid(columns),
+        }
+    }
+
+    pub fn when(mut self, media_condition: &str, length: SizesLength) -> Self {
+        self.conditions.push((media_condition.to_string(), length));
+        self
+    }
+
+    // Every width the image might be displayed at, for checking that
+    // we've generated big enough copies.
+    pub fn lengths(&self) -> impl Iterator<Item = &SizesLength> {
+        self.conditions
+            .iter()
+            .map(|(_, length)| length)
+            .chain(std::iter::once(&self.default))
+    }
+}
+
+// This is what browsers assume when there's no `sizes` attribute.
+impl Default for Sizes {
+    fn default() -> Self {
+        Self::vw(100.0)
+    }
+}
+
+impl SizesLength {
+    pub fn grid(columns: u32) -> Self {
+        // Round to two decimal places so the attribute stays readable.
+        let vw = (columns as f32 * 100.0 / grid_columns as f32 * 100.0).ceil() / 100.0;
+        SizesLength::Vw(vw)
+    }
+
+    // How many CSS pixels wide this is on a viewport of the given width.
+    pub fn to_px(&self, viewport_width_px: u32) -> u32 {
+        let to_px = |length: &SizesLength| length.to_px(viewport_width_px);
+
+        match self {
+            SizesLength::Vw(vw) => (vw / 100.0 * viewport_width_px as f32).ceil() as u32,
+            SizesLength::Px(px) => *px,
+            SizesLength::Min(lengths) => lengths.iter().map(to_px).min().unwrap_or(0),
+            SizesLength::Max(lengths) => lengths.iter().map(to_px).max().unwrap_or(0),
+            SizesLength::Clamp(lengths) => {
+                let [min, preferred, max] = &**lengths;
+                to_px(preferred).min(to_px(max)).max(to_px(min))
+            }
+        }
+    }
+}
+
+impl Display for SizesLength {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SizesLength::Vw(vw) => write!(f, "{}vw", vw),
+            SizesLength::Px(px) => write!(f, "{}px", px),
+            SizesLength::Min(lengths) => write!(f, "min({})", join(lengths)),
+            SizesLength::Max(lengths) => write!(f, "max({})", join(lengths)),
+            SizesLength::Clamp(lengths) => write!(f, "clamp({})", join(&**lengths)),
+        }
+    }
+}
+
+fn join(lengths: &[SizesLength]) -> String {
+    lengths
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Display for Sizes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (media_condition, length) in &self.conditions {
+            write!(f, "{} {}, ", media_condition, length)?;
+        }
+
+        write!(f, "{}", self.default)
+    }
+}
+
+impl FromStr for SizesLength {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let invalid_length = || {
+            format!(
+                "Invalid length: {}. Should look like 50vw, 300px, grid-52, or min(100vw, 640px).",
+                s
+            )
+        };
+
+        if let Some((function, arguments)) = s.strip_suffix(')').and_then(|s| s.split_once('(')) {
+            let lengths = split_top_level(arguments, ',')
+                .into_iter()
+                .map(str::parse)
+                .collect::<Result<Vec<SizesLength>, String>>()?;
+
+            return match (function.trim(), <[SizesLength; 3]>::try_from(lengths)) {
+                ("clamp", Ok(lengths)) => Ok(SizesLength::Clamp(Box::new(lengths))),
+                ("clamp", Err(_)) => Err(format!(
+                    "Invalid length: {}. clamp() takes a minimum, a preferred length, and a maximum.",
+                    s
+                )),
+                ("min", Ok(lengths)) => Ok(SizesLength::Min(lengths.to_vec())),
+                ("min", Err(lengths)) => Ok(SizesLength::Min(lengths)),
+                ("max", Ok(lengths)) => Ok(SizesLength::Max(lengths.to_vec())),
+                ("max", Err(lengths)) => Ok(SizesLength::Max(lengths)),
+                _ => Err(invalid_length()),
+            };
+        }
+
+        let parsed = if let Some(columns) = s.strip_prefix("grid-") {
+            columns.parse().ok().map(SizesLength::grid)
+        } else if let Some(vw) = s.strip_suffix("vw") {
+            vw.parse().ok().map(SizesLength::Vw)
+        } else if let Some(px) = s.strip_suffix("px") {
+            px.parse().ok().map(SizesLength::Px)
+        } else {
+            None
+        };
+
+        parsed.ok_or_else(invalid_length)
+    }
+}
+
+// Parses strings like "(max-width: 640px) 100vw, grid-52". The
+// last entry is the default and can't have a media condition.
+impl FromStr for Sizes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut entries: Vec<&str> = split_top_level(s, ',');
+
+        let default = entries
+            .pop()
+            .filter(|entry| !entry.is_empty())
+            .ok_or_else(|| "Sizes can't be empty.".to_string())?
+            .parse()?;
+
+        let conditions = entries
+            .into_iter()
+            .map(|entry| {
+                // The length is whatever comes after the media condition's
+                // closing parenthesis, like `100vw` or `min(100vw, 640px)`.
+                let (media_condition, length) = split_off_length(entry).ok_or_else(|| {
+                    format!(
+                        "Invalid sizes entry: {}. Should look like (max-width: 640px) 100vw.",
+                        entry
+                    )
+                })?;
+
+                Ok((media_condition.to_string(), length.parse()?))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            conditions,
+            default,
+        })
+    }
+}
+
+// Splits on separators that aren't inside parentheses, so that
+// "(max-width: 640px) min(100vw, 640px), 50vw" is two entries.
+fn split_top_level(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (index, character) in s.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if character == separator && depth == 0 => {
+                parts.push(s[start..index].trim());
+                start = index + character.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(s[start..].trim());
+    parts
+}
+
+// Splits "(max-width: 640px) 100vw" into the media condition and the
+// length, at the last space that isn't inside parentheses.
+fn split_off_length(entry: &str) -> Option<(&str, &str)> {
+    let mut depth = 0;
+    let mut last_space = None;
+
+    for (index, character) in entry.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ' ' if depth == 0 => last_space = Some(index),
+            _ => {}
+        }
+    }
+
+    let (media_condition, length) = entry.split_at(last_space?);
+    Some((media_condition.trim(), length.trim()))
+}
+
+cfg_if! {
+if #[cfg(feature = "build_time")] {
+
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+
+    impl ToTokens for SizesLength {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let quoted = match self {
+                SizesLength::Vw(vw) => quote! { assets::SizesLength::Vw(#vw) },
+                SizesLength::Px(px) => quote! { assets::SizesLength::Px(#px) },
+                SizesLength::Min(lengths) => quote! { assets::SizesLength::Min(vec![#(#lengths),*]) },
+                SizesLength::Max(lengths) => quote! { assets::SizesLength::Max(vec![#(#lengths),*]) },
+                SizesLength::Clamp(lengths) => {
+                    let [min, preferred, max] = &**lengths;
+                    quote! { assets::SizesLength::Clamp(Box::new([#min, #preferred, #max])) }
+                }
+            };
+
+            tokens.extend(quoted);
+        }
+    }
+
+    impl ToTokens for Sizes {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let conditions = self.conditions.iter().map(|(media_condition, length)| {
+                quote! { (#media_condition.to_string(), #length) }
+            });
+            let default = &self.default;
+
+            tokens.extend(quote! {
+                assets::Sizes {
+                    conditions: vec![#(#conditions),*],
+                    default: #default,
+                }
+            });
+        }
+    }
+
+}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_single_length() {
+        assert_eq!("50vw".parse(), Ok(Sizes::vw(50.0)));
+        assert_eq!(" 300px ".parse(), Ok(Sizes::px(300)));
+        assert_eq!("grid-52".parse(), Ok(Sizes::grid(52)));
+    }
+
+    #[test]
+    fn parses_media_conditions() {
+        let sizes: Sizes = "(max-width: 640px) 100vw, (min-width: 1280px) 640px, grid-52"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            sizes,
+            Sizes::grid(52)
+                .when("(max-width: 640px)", SizesLength::Vw(100.0))
+                .when("(min-width: 1280px)", SizesLength::Px(640))
+        );
+    }
+
+    #[test]
+    fn parses_comparison_functions() {
+        let sizes: Sizes = "(max-width: 640px) min(100vw, 640px), clamp(320px, 50vw, 960px)"
+            .parse()
+            .unwrap();
+
+        assert_eq!(sizes.conditions.len(), 1);
+        assert_eq!(sizes.conditions[0].0, "(max-width: 640px)");
+        assert_eq!(
+            sizes.conditions[0].1,
+            SizesLength::Min(vec![SizesLength::Vw(100.0), SizesLength::Px(640)])
+        );
+        assert_eq!(
+            sizes.default,
+            SizesLength::Clamp(Box::new([
+                SizesLength::Px(320),
+                SizesLength::Vw(50.0),
+                SizesLength::Px(960)
+            ]))
+        );
+        assert_eq!(
+            sizes.to_string(),
+            "(max-width: 640px) min(100vw, 640px), clamp(320px, 50vw, 960px)"
+        );
+    }
+
+    #[test]
+    fn converts_comparison_functions_to_px() {
+        let length: SizesLength = "clamp(320px, 50vw, 960px)".parse().unwrap();
+        assert_eq!(length.to_px(400), 320);
+        assert_eq!(length.to_px(1000), 500);
+        assert_eq!(length.to_px(4000), 960);
+
+        let length: SizesLength = "max(grid-52, 300px)".parse().unwrap();
+        assert_eq!(length.to_px(360), 300);
+    }
+
+    #[test]
+    fn rejects_invalid_sizes() {
+        assert!("".parse::<Sizes>().is_err());
+        assert!("50".parse::<Sizes>().is_err());
+        assert!("100vw, 50vw".parse::<Sizes>().is_err());
+        assert!("(max-width: 640px)100vw, 50vw".parse::<Sizes>().is_err());
+        assert!("clamp(1px, 2px)".parse::<SizesLength>().is_err());
+        assert!("calc(100vw - 2rem)".parse::<SizesLength>().is_err());
+    }
+}
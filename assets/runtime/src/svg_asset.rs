@@ -0,0 +1,120 @@
+use crate::built_assets_browser_prefix;
+use cfg_if::cfg_if;
+use std::{path::PathBuf, time::Duration};
+
+// An optimized SVG. It's saved to disk so it can be loaded by URL, and
+// its markup is kept so that it can also be inlined into the page.
+#[derive(PartialEq)]
+pub struct SvgAsset {
+    pub url_path: PathBuf, // Used for loading the asset in the browser.
+    pub url_path_starting_from_built_assets_dir: PathBuf, // Used for saving the asset to disk.
+    pub load_time_budget: Duration,
+    pub size_in_bytes: usize, // For checking performance budgets.
+    pub view_box: String,
+    // Attributes of the root <svg> element other than xmlns and viewBox,
+    // like fill="none" on stroked icons.
+    pub root_attributes: Vec<(String, String)>,
+    // Everything inside the root <svg> element, already minified.
+    pub inner_markup: String,
+}
+
+impl SvgAsset {
+    pub fn new(
+        url_path: PathBuf,
+        load_time_budget: Duration,
+        view_box: String,
+        root_attributes: Vec<(String, String)>,
+        inner_markup: String,
+    ) -> Self {
+        let url_path_starting_from_built_assets_dir = url_path
+            .strip_prefix(built_assets_browser_prefix())
+            .expect("Error stripping prefix.")
+            .to_path_buf();
+
+        let mut asset = Self {
+            url_path,
+            url_path_starting_from_built_assets_dir,
+            load_time_budget,
+            size_in_bytes: 0,
+            view_box,
+            root_attributes,
+            inner_markup,
+        };
+        asset.size_in_bytes = asset.to_file_contents().len();
+
+        #[cfg(feature = "build_time")]
+        asset.check_performance_budget();
+
+        asset
+    }
+
+    // The standalone SVG file that gets served at url_path.
+    pub fn to_file_contents(&self) -> String {
+        let root_attributes: String = self
+            .root_attributes
+            .iter()
+            .map(|(name, value)| format!(" {}=\"{}\"", name, value))
+            .collect();
+
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{}\"{}>{}</svg>",
+            self.view_box, root_attributes, self.inner_markup
+        )
+    }
+}
+
+cfg_if! {
+if #[cfg(feature = "build_time")] {
+    use crate::performance_budget::HasPerformanceBudget;
+    use proc_macro2::TokenStream;
+    use quote::{quote, ToTokens};
+
+    impl HasPerformanceBudget for SvgAsset {
+        fn load_time_budget(&self) -> Duration {
+            self.load_time_budget
+        }
+
+        fn size_in_bytes(&self) -> usize{
+            self.size_in_bytes
+        }
+
+        fn path_for_reporting_asset_over_budget(&self) -> &std::path::Path {
+            &self.url_path_starting_from_built_assets_dir
+        }
+    }
+
+    impl ToTokens for SvgAsset {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            log::info!("Converting SvgAsset to tokens.");
+
+            let url_path = self.url_path.to_str().unwrap();
+            let url_path_starting_from_built_assets_dir = self
+                .url_path_starting_from_built_assets_dir
+                .to_str()
+                .unwrap();
+            let load_time_budget_millis = self.load_time_budget.as_millis() as u64;
+            let size_in_bytes = self.size_in_bytes;
+            let view_box = &self.view_box;
+            let root_attributes = self.root_attributes.iter().map(|(name, value)| {
+                quote! { (#name.to_string(), #value.to_string()) }
+            });
+            let inner_markup = &self.inner_markup;
+
+            let quoted = quote! {
+                assets::SvgAsset {
+                    url_path: std::path::PathBuf::from(#url_path),
+                    url_path_starting_from_built_assets_dir: std::path::PathBuf::from(#url_path_starting_from_built_assets_dir),
+                    load_time_budget: std::time::Duration::from_millis(#load_time_budget_millis),
+                    size_in_bytes: #size_in_bytes,
+                    view_box: #view_box.to_string(),
+                    root_attributes: vec![#(#root_attributes),*],
+                    inner_markup: #inner_markup.to_string(),
+                }
+            };
+
+            tokens.extend(quoted);
+        }
+    }
+
+}
+}
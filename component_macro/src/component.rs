@@ -0,0 +1,252 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    spanned::Spanned, Attribute, Data, DeriveInput, Expr, Fields, GenericArgument, Ident,
+    PathArguments, Token, Type,
+};
+
+pub fn derive(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    match derive_builder(&input) {
+        Ok(output) => output.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn derive_builder(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let error_message = "#[derive(Component)] only works on structs with named fields.";
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(input.ident.span(), error_message));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new(input.ident.span(), error_message));
+    };
+
+    let props = fields
+        .named
+        .iter()
+        .map(Prop::from_field)
+        .collect::<syn::Result<Vec<Prop>>>()?;
+
+    let visibility = &input.vis;
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let required_arguments = props
+        .iter()
+        .filter(|prop| matches!(prop.kind, PropKind::Required))
+        .map(|prop| {
+            let name = &prop.name;
+            let ty = &prop.ty;
+            quote! { #name: #ty }
+        });
+
+    let initializers = props.iter().map(Prop::initializer);
+    let setters = props.iter().filter_map(|prop| prop.setter(visibility));
+
+    let output = quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #name #type_generics #where_clause {
+            #visibility fn new(#(#required_arguments),*) -> Self {
+                Self {
+                    #(#initializers),*
+                }
+            }
+
+            #(#setters)*
+        }
+    };
+
+    Ok(output)
+}
+
+struct Prop<'a> {
+    name: &'a Ident,
+    ty: &'a Type,
+    kind: PropKind,
+    has_setter: bool,
+    docs: Vec<&'a Attribute>,
+}
+
+enum PropKind {
+    // An argument to new().
+    Required,
+    // Starts as Default::default(), or the given expression.
+    Default(Option<Expr>),
+    // Markup that starts empty.
+    Slot,
+}
+
+impl<'a> Prop<'a> {
+    fn from_field(field: &'a syn::Field) -> syn::Result<Prop<'a>> {
+        let name = field
+            .ident
+            .as_ref()
+            .expect("Named fields should have a name.");
+
+        let mut kind = PropKind::Required;
+        // Where no_setter was given, for pointing errors at it.
+        let mut no_setter = None;
+
+        // A prop can only be one kind, so `slot` and `default` can't be combined.
+        let mut set_kind = |new_kind: PropKind, meta: &syn::meta::ParseNestedMeta| {
+            if !matches!(kind, PropKind::Required) {
+                return Err(meta
+                    .error("A prop can only have one of default, default = expression, or slot."));
+            }
+            kind = new_kind;
+            Ok(())
+        };
+
+        for attribute in field
+            .attrs
+            .iter()
+            .filter(|attribute| attribute.path().is_ident("component"))
+        {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    let expression = if meta.input.peek(Token![=]) {
+                        Some(meta.value()?.parse::<Expr>()?)
+                    } else {
+                        None
+                    };
+                    set_kind(PropKind::Default(expression), &meta)
+                } else if meta.path.is_ident("slot") {
+                    set_kind(PropKind::Slot, &meta)
+                } else if meta.path.is_ident("no_setter") {
+                    if no_setter.is_some() {
+                        return Err(meta.error("no_setter is already set."));
+                    }
+                    no_setter = Some(meta.path.span());
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        "Unknown component attribute. Try default, default = expression, slot, or no_setter.",
+                    ))
+                }
+            })?;
+        }
+
+        if let (Some(no_setter), PropKind::Required) = (no_setter, &kind) {
+            return Err(syn::Error::new(
+                no_setter,
+                "Required props are set in new(), so they never have a setter. Remove no_setter, or give this prop a default.",
+            ));
+        }
+
+        // Slots start empty and their setters render into them, so they
+        // have to hold Markup.
+        let slot_type = option_inner_type(&field.ty).unwrap_or(&field.ty);
+        if matches!(kind, PropKind::Slot) && !is_markup(slot_type) {
+            return Err(syn::Error::new(
+                field.ty.span(),
+                "Slots should be Markup or Option<Markup>.",
+            ));
+        }
+
+        let docs = field
+            .attrs
+            .iter()
+            .filter(|attribute| attribute.path().is_ident("doc"))
+            .collect();
+
+        Ok(Prop {
+            name,
+            ty: &field.ty,
+            kind,
+            has_setter: no_setter.is_none(),
+            docs,
+        })
+    }
+
+    fn initializer(&self) -> TokenStream2 {
+        let name = self.name;
+
+        let value = match &self.kind {
+            PropKind::Required => quote! { #name },
+            PropKind::Default(Some(expression)) => quote! { #expression },
+            PropKind::Default(None) => quote! { ::std::default::Default::default() },
+            PropKind::Slot if option_inner_type(self.ty).is_some() => {
+                quote! { ::std::option::Option::None }
+            }
+            PropKind::Slot => quote! { ::maud::PreEscaped(::std::string::String::new()) },
+        };
+
+        quote! { #name: #value }
+    }
+
+    fn setter(&self, visibility: &syn::Visibility) -> Option<TokenStream2> {
+        if !self.has_setter || matches!(self.kind, PropKind::Required) {
+            return None;
+        }
+
+        let name = self.name;
+        let docs = &self.docs;
+        let is_option = option_inner_type(self.ty).is_some();
+
+        let (argument_type, value) = match (&self.kind, option_inner_type(self.ty)) {
+            (PropKind::Slot, _) => (
+                quote! { impl ::maud::Render },
+                quote! { ::maud::Render::render(&#name) },
+            ),
+            (_, Some(inner_type)) => (
+                quote! { impl ::std::convert::Into<#inner_type> },
+                quote! { #name.into() },
+            ),
+            (_, None) => {
+                let ty = self.ty;
+                (
+                    quote! { impl ::std::convert::Into<#ty> },
+                    quote! { #name.into() },
+                )
+            }
+        };
+
+        let value = if is_option {
+            quote! { ::std::option::Option::Some(#value) }
+        } else {
+            value
+        };
+
+        Some(quote! {
+            #(#docs)*
+            #visibility fn #name(mut self, #name: #argument_type) -> Self {
+                self.#name = #value;
+                self
+            }
+        })
+    }
+}
+
+fn is_markup(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+
+    type_path
+        .path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Markup" && segment.arguments.is_empty())
+}
+
+// The T in Option<T>, if the type is an Option.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+
+    match arguments.args.first()? {
+        GenericArgument::Type(inner_type) => Some(inner_type),
+        _ => None,
+    }
+}
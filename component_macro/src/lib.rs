@@ -0,0 +1,38 @@
+use proc_macro::TokenStream;
+
+mod component;
+
+/// Generates a builder for a Maud component from its fields.
+///
+/// Fields without a `#[component(...)]` attribute are required, and are
+/// the arguments to the generated `new()` in the order they're declared.
+/// Every other field gets a setter of the same name:
+///
+/// - `#[component(default)]` starts as `Default::default()`.
+/// - `#[component(default = expression)]` starts as the expression.
+/// - `#[component(slot)]` is a `Markup` that starts empty, and its setter
+///   takes anything that implements `Render`. A component can have as many
+///   named slots as it needs, like `header` and `footer`.
+///
+/// Setters for `Option` fields take the inner value. Add `no_setter` to
+/// a default field to write its setter by hand. Components still
+/// implement `Render` themselves.
+///
+/// ```ignore
+/// #[derive(Component)]
+/// pub struct Card<'a> {
+///     route: Route,
+///     #[component(default)]
+///     class: &'a str,
+///     #[component(slot)]
+///     header: Markup,
+///     #[component(slot)]
+///     footer: Option<Markup>,
+/// }
+///
+/// Card::new(Route::Home).class("w-grid-52").header("Hello!")
+/// ```
+#[proc_macro_derive(Component, attributes(component))]
+pub fn derive_component(input: TokenStream) -> TokenStream {
+    component::derive(input)
+}
@@ -0,0 +1,11 @@
+// Checks the errors #[derive(Component)] gives for props it can't build.
+// Run with TRYBUILD=overwrite to update the expected errors.
+#[test]
+fn ui() {
+    let tests = trybuild::TestCases::new();
+    tests.pass("tests/ui/multiple_slots.rs");
+    tests.compile_fail("tests/ui/unknown_attribute.rs");
+    tests.compile_fail("tests/ui/no_setter_on_required_prop.rs");
+    tests.compile_fail("tests/ui/slot_and_default.rs");
+    tests.compile_fail("tests/ui/slot_not_markup.rs");
+}
@@ -0,0 +1,33 @@
+use component_macro::Component;
+use maud::{html, Markup, Render};
+
+#[derive(Component)]
+pub struct Card {
+    title: &'static str,
+    #[component(slot)]
+    header: Markup,
+    #[component(slot)]
+    footer: Option<Markup>,
+}
+
+impl Render for Card {
+    fn render(&self) -> Markup {
+        html! {
+            h2 { (self.title) }
+            (self.header)
+            @if let Some(footer) = &self.footer {
+                (footer)
+            }
+        }
+    }
+}
+
+fn main() {
+    let card = Card::new("Hello!")
+        .header("Header")
+        .footer(html! { p { "Footer" } });
+    assert_eq!(
+        card.render().into_string(),
+        "<h2>Hello!</h2>Header<p>Footer</p>"
+    );
+}
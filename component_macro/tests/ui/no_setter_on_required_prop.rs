@@ -0,0 +1,9 @@
+use component_macro::Component;
+
+#[derive(Component)]
+pub struct Card {
+    #[component(no_setter)]
+    title: &'static str,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use component_macro::Component;
+use maud::Markup;
+
+#[derive(Component)]
+pub struct Card {
+    #[component(slot, default)]
+    header: Markup,
+}
+
+fn main() {}
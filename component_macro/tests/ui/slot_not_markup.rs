@@ -0,0 +1,9 @@
+use component_macro::Component;
+
+#[derive(Component)]
+pub struct Card {
+    #[component(slot)]
+    header: String,
+}
+
+fn main() {}
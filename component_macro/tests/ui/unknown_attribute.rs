@@ -0,0 +1,9 @@
+use component_macro::Component;
+
+#[derive(Component)]
+pub struct Card {
+    #[component(optional)]
+    title: &'static str,
+}
+
+fn main() {}
@@ -4,13 +4,16 @@
 //
 // import init, { greet } from './browser.js'
 //
-// The `from` part of this import gets replaced with the actual filename
-// when we include this file in the HTML.
-import wasmMain from './{browser_js_filename}'
+// This gets replaced with an import of browser.js when we include this
+// file in the HTML. If browser.js is small enough to inline, it's
+// replaced with browser.js itself, with its default export as `wasmMain`.
+{browser_js}
 
 async function main () {
-  // Run our wasm code's main function.
-  await wasmMain()
+  // Run our wasm code's main function. We pass the wasm's URL because
+  // wasm-bindgen otherwise looks for it next to browser.js, which
+  // doesn't work when browser.js is inlined.
+  await wasmMain('{browser_wasm_url}')
 }
 
 main()
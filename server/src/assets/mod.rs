@@ -3,17 +3,31 @@ use once_cell::sync::Lazy;
 
 pub static ASSETS: Lazy<Assets> = Lazy::new(Assets::new);
 
+// The origin of a CDN we load things from, like "https://cdn.example.com",
+// so that we can connect to it while the page is still loading.
+static CDN_ORIGIN: Lazy<Option<String>> = Lazy::new(|| {
+    std::env::var("CDN_ORIGIN")
+        .ok()
+        .filter(|origin| !origin.is_empty())
+});
+
+// This defines the ProjectFlowers struct, with a field for each image in
+// the folder named after its file name. Alt text comes from the folder's
+// alt.toml.
+assets::include_images_in_folder!(
+    struct_name: ProjectFlowers,
+    path_to_images_dir: "server/src/assets/images/project_flowers",
+    placeholder: automatic_color,
+    formats: [avif, webp, jpeg],
+    sizes: "grid-52",
+);
+
 pub struct Assets {
     pub css: CssAsset,
     pub browser_crate: BrowserCrateAsset,
     pub favicon: FileAsset,
-    pub logo: FileAsset,
-    pub project_flower_one: ImageAsset,
-    pub project_flower_two: ImageAsset,
-    pub project_flower_three: ImageAsset,
-    pub project_flower_four: ImageAsset,
-    pub project_flower_five: ImageAsset,
-    pub project_flower_six: ImageAsset,
+    pub logo: SvgAsset,
+    pub project_flowers: ProjectFlowers,
 
     // Fonts
     pub fugi: FontAsset,
@@ -29,6 +43,7 @@ impl Assets {
             path_to_input_file: "server/src/assets/main.css",
             url_path: "built-assets/built.css",
             performance_budget_millis: 150,
+            critical_css_performance_budget_millis: 150,
         );
 
         let browser_crate = assets::include_browser_crate!(
@@ -37,6 +52,7 @@ impl Assets {
             js_performance_budget_millis: 150,
             wasm_url_path: "built-assets/browser_bg.wasm",
             wasm_performance_budget_millis: 150,
+            critical: true,
         );
 
         let favicon = assets::include_file!(
@@ -45,97 +61,72 @@ impl Assets {
             performance_budget_millis: 150,
         );
 
-        let logo = assets::include_file!(
-            path_to_input_file: "server/src/assets/images/logo.png",
-            url_path: "built-assets/favicon.ico",
-            performance_budget_millis: 275,
-        );
-
-        // This image is decorative, so we skip the alt text.
-        let project_flower_one = assets::include_image!(
-            path_to_image: "server/src/assets/images/project_flower_one.png",
-            alt: "",
-            placeholder: automatic_color,
-        );
-
-        // This image is decorative, so we skip the alt text.
-        let project_flower_two = assets::include_image!(
-            path_to_image: "server/src/assets/images/project_flower_two.png",
-            alt: "",
-            placeholder: automatic_color,
-        );
-
-        // This image is decorative, so we skip the alt text.
-        let project_flower_three = assets::include_image!(
-            path_to_image: "server/src/assets/images/project_flower_three.png",
-            alt: "",
-            placeholder: automatic_color,
-        );
-
-        // This image is decorative, so we skip the alt text.
-        let project_flower_four = assets::include_image!(
-            path_to_image: "server/src/assets/images/project_flower_four.png",
-            alt: "",
-            placeholder: automatic_color,
-        );
-
-        // This image is decorative, so we skip the alt text.
-        let project_flower_five = assets::include_image!(
-            path_to_image: "server/src/assets/images/project_flower_five.png",
-            alt: "",
-            placeholder: automatic_color,
-        );
-
-        // This image is decorative, so we skip the alt text.
-        let project_flower_six = assets::include_image!(
-            path_to_image: "server/src/assets/images/project_flower_six.png",
-            alt: "",
-            placeholder: automatic_color,
+        let logo = assets::include_svg!(
+            path_to_input_file: "server/src/assets/images/logo.svg",
+            url_path: "built-assets/logo.svg",
+            performance_budget_millis: 150,
         );
 
         // Fonts
 
         let fugi = assets::include_font!(
             path_to_input_file: "server/src/assets/fonts/fugi.ttf",
-            url_path: "built-assets/fonts/fugi.ttf",
+            url_path: "built-assets/fonts/fugi.woff2",
             performance_budget_millis: 275,
+            family: "Fugi",
+            weight: 400,
+            subset: auto,
         );
 
         let aurora_grotesk_bold = assets::include_font!(
             path_to_input_file: "server/src/assets/fonts/aurora_grotesk_bold.otf",
-            url_path: "built-assets/fonts/aurora-grotesk-bold.otf",
+            url_path: "built-assets/fonts/aurora-grotesk-bold.woff2",
             performance_budget_millis: 275,
+            family: "Aurora Grotesk",
+            weight: 700,
+            subset: auto,
         );
 
         let aurora_grotesk_medium = assets::include_font!(
             path_to_input_file: "server/src/assets/fonts/aurora_grotesk_medium.otf",
-            url_path: "built-assets/fonts/aurora-grotesk-medium.otf",
+            url_path: "built-assets/fonts/aurora-grotesk-medium.woff2",
             performance_budget_millis: 275,
+            family: "Aurora Grotesk",
+            weight: 500,
+            critical: true,
+            subset: auto,
         );
 
         let aurora_grotesk_light = assets::include_font!(
             path_to_input_file: "server/src/assets/fonts/aurora_grotesk_light.otf",
-            url_path: "built-assets/fonts/aurora-grotesk-light.otf",
+            url_path: "built-assets/fonts/aurora-grotesk-light.woff2",
             performance_budget_millis: 275,
+            family: "Aurora Grotesk",
+            weight: 300,
+            subset: auto,
         );
 
         let clearface_bold = assets::include_font!(
             path_to_input_file: "server/src/assets/fonts/clearface_bold.ttf",
-            url_path: "built-assets/fonts/clearface-bold.ttf",
+            url_path: "built-assets/fonts/clearface-bold.woff2",
             performance_budget_millis: 275,
+            family: "Clearface",
+            weight: 700,
+            critical: true,
+            subset: auto,
         );
 
+        // This needs to come after all of the other asset macros, since it
+        // lists anything in the built assets folder they didn't produce.
+        // Run `./scripts clean_built_assets` to remove them.
+        assets::remove_stale_built_assets!();
+
         Self {
             css,
             browser_crate,
             favicon,
             logo,
-            project_flower_one,
-            project_flower_two,
-            project_flower_three,
-            project_flower_four,
-            project_flower_five,
-            project_flower_six,
+            project_flowers: ProjectFlowers::new(),
 
             fugi,
             aurora_grotesk_bold,
@@ -146,6 +137,36 @@ impl Assets {
     }
 }
 
+impl Assets {
+    pub fn fonts(&self) -> [&FontAsset; 5] {
+        [
+            &self.fugi,
+            &self.aurora_grotesk_bold,
+            &self.aurora_grotesk_medium,
+            &self.aurora_grotesk_light,
+            &self.clearface_bold,
+        ]
+    }
+
+    // Links for the head that let the browser start fetching critical
+    // assets right away, instead of discovering them one after another.
+    pub fn resource_hints(&self) -> Vec<ResourceHint> {
+        let preconnect = CDN_ORIGIN.iter().map(|origin| ResourceHint::Preconnect {
+            origin: origin.clone(),
+        });
+
+        let font_preloads = self
+            .fonts()
+            .into_iter()
+            .filter_map(FontAsset::resource_hint);
+
+        preconnect
+            .chain(self.browser_crate.resource_hints())
+            .chain(font_preloads)
+            .collect()
+    }
+}
+
 impl Default for Assets {
     fn default() -> Self {
         Self::new()
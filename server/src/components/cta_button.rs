@@ -1,38 +1,25 @@
+use crate::components::Component;
+use crate::css_class_groups::merge_classes;
+use ::assets::class;
 use maud::{html, Markup, Render};
 
+#[derive(Component)]
 pub struct CtaButton<'a> {
+    #[component(default)]
     class: &'a str,
+    #[component(slot)]
     slot: Markup,
 }
 
-impl<'a> CtaButton<'a> {
-    pub fn new() -> Self {
-        Self {
-            class: "",
-            slot: html! { "" },
-        }
-    }
-
-    pub fn class(mut self, class: impl Into<&'a str>) -> Self {
-        self.class = class.into();
-        self
-    }
-
-    pub fn slot(mut self, slot: impl Render) -> Self {
-        self.slot = slot.render();
-        self
-    }
-}
-
 impl Render for CtaButton<'_> {
     fn render(&self) -> Markup {
+        let class = merge_classes([
+            class!("cta bg-petal-salmon text-grid-4 px-grid-4 py-grid-2 rounded-sm border"),
+            self.class,
+        ]);
+
         html! {
-            button
-                class={
-                    "cta bg-petal-salmon text-grid-4 px-grid-4 py-grid-2 rounded-sm border "
-                    (self.class)
-                }
-            {
+            button class=(class) {
                 (self.slot)
             }
         }
@@ -0,0 +1,19 @@
+use crate::assets::ASSETS;
+use maud::{html, Markup, PreEscaped};
+
+// The @font-face rules for our fonts, generated from the same FontAssets
+// that build them so the URLs can't drift apart. Critical fonts are
+// preloaded by `resource_hints()`.
+pub fn font_faces() -> Markup {
+    let font_face_rules: String = ASSETS
+        .fonts()
+        .iter()
+        .map(|font| font.to_font_face_rule())
+        .collect();
+
+    html! {
+        style {
+            (PreEscaped(font_face_rules))
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use assets::ResourceHint;
+use std::cell::RefCell;
+
+// Components render before the layout that contains them, since
+// `layout()` takes the already rendered body as an argument. So
+// components that need a resource hint, like a preload for the largest
+// contentful paint image, push it here, and `layout()` takes everything
+// when it renders the head and the Link header.
+//
+// Each request renders on a single thread without awaiting in between,
+// so a thread local is enough to keep requests apart.
+thread_local! {
+    static RESOURCE_HINTS: RefCell<Vec<ResourceHint>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn push_resource_hint(resource_hint: ResourceHint) {
+    RESOURCE_HINTS.with(|resource_hints| {
+        let mut resource_hints = resource_hints.borrow_mut();
+
+        // The same component might be rendered more than once on a page.
+        if !resource_hints.contains(&resource_hint) {
+            resource_hints.push(resource_hint);
+        }
+    });
+}
+
+pub fn take_resource_hints() -> Vec<ResourceHint> {
+    RESOURCE_HINTS.with(|resource_hints| resource_hints.take())
+}
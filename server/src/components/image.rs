@@ -1,67 +1,187 @@
-use assets::{ImageAsset, Placeholder};
+use crate::components::{push_resource_hint, Component};
+use crate::css_class_groups::merge_classes;
+use ::assets::class;
+use assets::{ImageAsset, Placeholder, ResourceHint, Sizes};
 use maud::{html, Markup, Render};
 
+#[derive(Component)]
 pub struct Image<'a> {
     pub asset: &'a ImageAsset,
+    #[component(default)]
     pub class: &'a str,
+    /// Overrides the sizes the image was included with.
+    #[component(default)]
+    pub sizes: Option<Sizes>,
+    #[component(default, no_setter)]
+    pub above_the_fold: bool,
+    #[component(default, no_setter)]
+    pub largest_contentful_paint: bool,
 }
 
-impl<'a> Image<'a> {
-    pub fn new(asset: &'a ImageAsset) -> Self {
-        Self { asset, class: "" }
+impl Image<'_> {
+    // Images are lazy loaded by default. Images that are visible
+    // when the page first loads should be loaded eagerly instead.
+    #[allow(dead_code)]
+    pub fn above_the_fold(mut self) -> Self {
+        self.above_the_fold = true;
+        self
     }
 
-    #[allow(dead_code)]
-    pub fn class(mut self, class: impl Into<&'a str>) -> Self {
-        self.class = class.into();
+    // For the biggest thing visible when the page first loads. It's
+    // loaded eagerly at high priority and preloaded from the head,
+    // so that the browser starts fetching it before it sees the img.
+    pub fn largest_contentful_paint(mut self) -> Self {
+        self.above_the_fold = true;
+        self.largest_contentful_paint = true;
         self
     }
+
+    fn sizes_or_default(&self) -> &Sizes {
+        self.sizes.as_ref().unwrap_or(&self.asset.sizes)
+    }
+
+    fn loading(&self) -> &'static str {
+        if self.above_the_fold {
+            "eager"
+        } else {
+            "lazy"
+        }
+    }
+
+    fn fetchpriority(&self) -> Option<&'static str> {
+        self.largest_contentful_paint.then_some("high")
+    }
+
+    // The preload has to match what the picture element would choose, or
+    // the browser downloads the image twice. So we preload the most
+    // preferred source, with its type so that browsers that can't decode
+    // it skip the preload. Those browsers just fetch the image when they
+    // get to the picture element.
+    fn preload(&self) -> ResourceHint {
+        let sizes = self.sizes_or_default().to_string();
+
+        match self.asset.sources.first() {
+            Some(source) => ResourceHint::ImagePreload {
+                href: None,
+                srcset: source.srcset.clone(),
+                sizes,
+                mime_type: Some(source.mime_type.clone()),
+            },
+            None => ResourceHint::ImagePreload {
+                href: Some(self.asset.src.clone()),
+                srcset: self.asset.srcset.clone(),
+                sizes,
+                mime_type: None,
+            },
+        }
+    }
 }
 
 impl Render for Image<'_> {
     fn render(&self) -> Markup {
+        if self.largest_contentful_paint {
+            push_resource_hint(self.preload());
+        }
+
         match &self.asset.placeholder {
             Placeholder::Color { css_string } => {
-                image_with_color_placeholder(self.class, self.asset, css_string)
+                image_with_css_placeholder(self, class!("bg-[var(--placeholder)]"), css_string)
             }
-            Placeholder::Lqip { data_uri } => image_with_lqip(self.class, self.asset, data_uri),
+            Placeholder::ThumbHash { css_background, .. } => image_with_css_placeholder(
+                self,
+                class!("[background:var(--placeholder)]"),
+                css_background,
+            ),
+            Placeholder::Lqip { data_uri } => image_with_lqip(self, data_uri),
         }
     }
 }
 
-fn image_with_color_placeholder(
-    class: &str,
-    asset: &ImageAsset,
-    placeholder_color_css_string: &str,
+// The picture element gets `display: contents` so that the img inside it
+// is laid out as if the picture wasn't there, and classes like `self-end`
+// still apply to it.
+fn picture(image: &Image, img: Markup) -> Markup {
+    let sizes = image.sizes_or_default();
+
+    html!(
+        picture class=(class!("contents")) {
+            @for source in &image.asset.sources {
+                source type=(source.mime_type) srcset=(source.srcset) sizes=(sizes);
+            }
+
+            (img)
+        }
+    )
+}
+
+// The width and height attributes are the image's intrinsic size. CSS
+// still decides how big it's displayed, but the browser can use them to
+// reserve space with the right aspect ratio before the image loads.
+//
+// Color and ThumbHash placeholders are both just a background on the img,
+// which the image covers once it loads. The background is set by a class
+// reading a custom property, rather than an inline style, so that callers
+// can override it with a class like `bg-slate`.
+fn image_with_css_placeholder(
+    image: &Image,
+    placeholder_class: &str,
+    placeholder_value: &str,
 ) -> Markup {
-    html!(img
-        class={(class) " select-none"}
-        style={"background-color: " (placeholder_color_css_string)}
-        alt=(asset.alt)
-        src=(asset.src)
-        srcset=(asset.srcset);
+    let asset = image.asset;
+    let class = merge_classes([class!("select-none"), placeholder_class, image.class]);
+
+    picture(
+        image,
+        html!(img
+            class=(class)
+            style={ "--placeholder: " (placeholder_value) ";" }
+            alt=(asset.alt)
+            width=(asset.width)
+            height=(asset.height)
+            loading=(image.loading())
+            decoding="async"
+            fetchpriority=[image.fetchpriority()]
+            src=(asset.src)
+            srcset=(asset.srcset)
+            sizes=(image.sizes_or_default());
+        ),
     )
 }
 
-fn image_with_lqip(class: &str, asset: &ImageAsset, data_uri: &str) -> Markup {
+fn image_with_lqip(image: &Image, data_uri: &str) -> Markup {
+    let asset = image.asset;
+    let class = merge_classes([class!("select-none relative overflow-hidden"), image.class]);
+
     html!(
         div
-            class={"select-none relative overflow-hidden " (class)}
+            class=(class)
         {
 
             // LQIP.
             img
                 alt=(asset.alt)
-                class="shrink-0 min-w-full min-h-full object-cover"
+                class=(class!("shrink-0 min-w-full min-h-full object-cover"))
                 style="image-rendering: pixelated; image-rendering: -moz-crisp-edges; image-rendering: crisp-edges;"
+                width=(asset.width)
+                height=(asset.height)
                 src=(data_uri);
 
             // Actual image.
-            img
-                alt=(asset.alt)
-                class="absolute top-0 left-0 min-w-full min-h-full object-cover"
-                src=(asset.src)
-                srcset=(asset.srcset);
+            (picture(
+                image,
+                html!(img
+                    alt=(asset.alt)
+                    class=(class!("absolute top-0 left-0 min-w-full min-h-full object-cover"))
+                    width=(asset.width)
+                    height=(asset.height)
+                    loading=(image.loading())
+                    decoding="async"
+                    fetchpriority=[image.fetchpriority()]
+                    src=(asset.src)
+                    srcset=(asset.srcset)
+                    sizes=(image.sizes_or_default());
+                ),
+            ))
         }
     )
 }
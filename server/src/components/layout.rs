@@ -1,99 +1,160 @@
 use crate::assets::ASSETS;
 use crate::components::*;
-use crate::extensions::*;
+use crate::critical_css::critical_css;
+use crate::link_header::record_link_header;
 use crate::routes::Route;
+use ::assets::{class, ResourceHint};
 use chrono::{Datelike, Utc};
 use maud::{html, Markup, PreEscaped, DOCTYPE};
 
-pub fn layout(title: &'static str, content: Markup) -> Markup {
+pub fn layout(route: Route, title: &'static str, content: Markup) -> Markup {
     let current_year = Utc::now().year();
 
-    html! {
-        (DOCTYPE)
-        html lang="en" {
-            head {
-                meta charset="UTF-8";
-                link rel="icon" href=(ASSETS.favicon.url_path.to_string_lossy().to_string());
-                meta name="viewport" content="width=device-width, initial-scale=1.0";
-                meta http_equiv="X-UA-Compatible" content="ie=edge";
-                meta name="description" content="rainsound.ai: Custom AI & Web App Development";
-                (stylesheet(&ASSETS.css))
-                title {
-                    (title)
+    // The content has already been rendered, so any components in it
+    // that need a resource hint have pushed it by now.
+    let head_resource_hints: Vec<ResourceHint> = ASSETS
+        .resource_hints()
+        .into_iter()
+        .chain(take_resource_hints())
+        .collect();
+
+    let body = html! {
+        body class=(class!("bg-slate text-white min-h-screen flex flex-col font-aurora-grotesk text-grid-4")) {
+            header class=(class!("h-grid-10 px-grid-7 flex justify-between items-center text-neutral bg-slate whitespace-nowrap z-20 text-grid-3")) {
+                (Link::no_underline(Route::Home)
+                    .class(class!("flex items-center gap-grid-1 no-underline"))
+                    .slot(html! {
+                        // Decorative, since the name is right next to it.
+                        (Svg::new(&ASSETS.logo).class(class!("h-grid-4 w-grid-4")))
+                        "rainsound.ai"
+                    })
+                )
+
+                div class=(class!("flex gap-grid-4")) {
+                    // (Link::no_underline(Route::Paurtfaurliaur)
+                    //     .class("text-slate")
+                    //     .slot("Paurtfaurliaur")
+                    // )
+                    (Link::no_underline(Route::Portfolio)
+                        .slot("Portfolio")
+                    )
+                    (Link::no_underline(Route::Contact)
+                        .slot("Contact")
+                    )
                 }
             }
 
-            body class="bg-slate text-white min-h-screen flex flex-col font-aurora-grotesk text-grid-4" {
-                header class="h-grid-10 px-grid-7 flex justify-between items-center text-neutral bg-slate whitespace-nowrap z-20 text-grid-3" {
+            main class=(class!("px-grid-7 grow flex flex-col items-center overflow-hidden")) {
+                (content)
+            }
+
+            footer
+                class=(class!("h-grid-33 px-grid-10 w-full grid grid-cols-3 text-grid-2 items-center text-neutral whitespace-nowrap"))
+            {
+                a href="/" class=(class!("flex items-center gap-grid-1 text-grid-3")) {
+                    // Decorative, since the name is right next to it.
+                    (Svg::new(&ASSETS.logo).class(class!("h-grid-5 w-grid-5")))
+                    "rainsound.ai"
+                }
+
+                div class=(class!("flex gap-grid-4 justify-between")) {
+
                     (Link::no_underline(Route::Home)
-                        .class("flex items-center gap-grid-1 no-underline")
-                        .slot(html! {
-                            img src=(ASSETS.logo.url_path.to_string()) alt="rainsound.ai logo" class="h-grid-4 w-grid-4" {
-                                "rainsound.ai"
-                            }
-                        })
+                        .slot("Home")
+                    )
+                    (Link::no_underline(Route::Portfolio)
+                        .slot("Portfolio")
+                    )
+                    (Link::no_underline(Route::Contact)
+                        .slot("Contact")
                     )
-
-                    div class="flex gap-grid-4" {
-                        // (Link::no_underline(Route::Paurtfaurliaur)
-                        //     .class("text-slate")
-                        //     .slot("Paurtfaurliaur")
-                        // )
-                        (Link::no_underline(Route::Portfolio)
-                            .slot("Portfolio")
-                        )
-                        (Link::no_underline(Route::Contact)
-                            .slot("Contact")
-                        )
-                    }
                 }
 
-                main class="px-grid-7 grow flex flex-col items-center overflow-hidden" {
-                    (content)
+                div class=(class!("justify-self-end")) {
+                    "© rainsound.ai " (current_year) ". We love our clients!"
                 }
+            }
 
-                footer
-                    class="h-grid-33 px-grid-10 w-full grid grid-cols-3 text-grid-2 items-center text-neutral whitespace-nowrap"
-                {
-                    a href="/" class="flex items-center gap-grid-1 text-grid-3" {
-                        img src=(ASSETS.logo.url_path.to_string()) alt="rainsound.ai logo" class="h-grid-5 w-grid-5" {
-                            "rainsound.ai"
-                        }
-                    }
+            (main_js())
+        }
+    };
 
-                    div class="flex gap-grid-4 justify-between" {
-
-                        (Link::no_underline(Route::Home)
-                            .slot("Home")
-                        )
-                        (Link::no_underline(Route::Portfolio)
-                            .slot("Portfolio")
-                        )
-                        (Link::no_underline(Route::Contact)
-                            .slot("Contact")
-                        )
-                    }
+    // Only the rules this page uses are inlined, so that it can render
+    // before the full stylesheet has loaded. If the whole stylesheet is
+    // small enough to inline, there's no need to split it.
+    let critical_css = (!ASSETS.css.should_inline()).then(|| critical_css(route, &body.0));
+
+    // The head doesn't preload the full stylesheet, so that it doesn't
+    // compete with the critical assets once the browser is parsing the
+    // page. But the Link header can reach the browser while we're still
+    // rendering, when there's nothing else to fetch.
+    let stylesheet_preload = critical_css.is_some().then(|| ASSETS.css.resource_hint());
+    let link_header_hints: Vec<ResourceHint> = head_resource_hints
+        .iter()
+        .cloned()
+        .chain(stylesheet_preload)
+        .collect();
+    record_link_header(route, &link_header_hints);
 
-                    div class="justify-self-end" {
-                        "© rainsound.ai " (current_year) ". We love our clients!"
+    html! {
+        (DOCTYPE)
+        html lang="en" {
+            head {
+                meta charset="UTF-8";
+                link rel="icon" href=(ASSETS.favicon.url_path.to_string_lossy().to_string());
+                meta name="viewport" content="width=device-width, initial-scale=1.0";
+                meta http_equiv="X-UA-Compatible" content="ie=edge";
+                meta name="description" content="rainsound.ai: Custom AI & Web App Development";
+                (resource_hints(&head_resource_hints))
+                (font_faces())
+                @if let Some(critical_css) = &critical_css {
+                    style {
+                        (PreEscaped(critical_css.css.as_str()))
                     }
+                    (async_stylesheet(&ASSETS.css))
+                } @else {
+                    (stylesheet(&ASSETS.css))
+                }
+                title {
+                    (title)
                 }
-
-                (main_js())
             }
+
+            (body)
         }
     }
 }
 
 fn main_js() -> Markup {
-    let browser_js_path = ASSETS
+    let browser_js = &ASSETS.browser_crate.js;
+
+    // Small enough JS is inlined to save a round trip. See `JsAsset::should_inline`.
+    // An inline module can't be imported, so instead of importing browser.js
+    // we paste it in above our code, and turn its default export into a
+    // variable with the name our code expects.
+    let browser_js = if browser_js.should_inline() {
+        let (before, after) = browser_js
+            .contents
+            .split_once("export default ")
+            .expect("browser.js should have a default export, since wasm-bindgen generates one.");
+        format!("{}const wasmMain = {}", before, after)
+    } else {
+        // Module specifiers need to start with ./ to count as relative URLs.
+        format!(
+            "import wasmMain from './{}'",
+            browser_js.url_path.to_string_lossy()
+        )
+    };
+    let browser_wasm_url = ASSETS
         .browser_crate
-        .js
+        .wasm
         .url_path
         .to_string_lossy()
         .to_string();
-    let contents =
-        include_str!("../assets/main.js").replace("{browser_js_filename}", &browser_js_path);
+
+    let contents = include_str!("../assets/main.js")
+        .replace("{browser_js}", &browser_js)
+        .replace("{browser_wasm_url}", &browser_wasm_url);
     html! {
         script type="module" {
             (PreEscaped(contents))
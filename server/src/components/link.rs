@@ -1,61 +1,41 @@
+use crate::components::Component;
+use crate::css_class_groups::merge_classes;
 use crate::routes::Route;
+use ::assets::class;
 use maud::{html, Markup, Render};
 
+#[derive(Component)]
 pub struct Link<'a> {
     pub route: Route,
+    pub variant: LinkVariant,
+    #[component(slot)]
     pub slot: Markup,
+    #[component(default)]
     pub class: &'a str,
-    pub variant: LinkVariant,
 }
 
-impl<'a> Link<'a> {
+impl Link<'_> {
     pub fn underline(route: Route) -> Self {
-        Self {
-            route,
-            slot: html! { "" },
-            class: "",
-            variant: LinkVariant::Underline,
-        }
+        Self::new(route, LinkVariant::Underline)
     }
 
     pub fn no_underline(route: Route) -> Self {
-        Self {
-            route,
-            slot: html! { "" },
-            class: "",
-            variant: LinkVariant::NoUnderline,
-        }
+        Self::new(route, LinkVariant::NoUnderline)
     }
 
     pub fn button(route: Route) -> Self {
-        Self {
-            route,
-            slot: html! { "" },
-            class: "",
-            variant: LinkVariant::Button,
-        }
-    }
-
-    pub fn slot(mut self, slot: impl Render) -> Self {
-        self.slot = slot.render();
-        self
-    }
-
-    pub fn class(mut self, class: impl Into<&'a str>) -> Self {
-        self.class = class.into();
-        self
+        Self::new(route, LinkVariant::Button)
     }
 }
 
 impl Render for Link<'_> {
     fn render(&self) -> Markup {
-        let class = match self.variant {
-            LinkVariant::NoUnderline => format!("{} no-underline", self.class),
-            LinkVariant::Underline => {
-                format!("{} underline decoration-2 underline-offset-2", self.class)
-            }
-            LinkVariant::Button => format!("{} border px-2 align-middle rounded-md", self.class),
+        let variant_class = match self.variant {
+            LinkVariant::NoUnderline => class!("no-underline"),
+            LinkVariant::Underline => class!("underline decoration-2 underline-offset-2"),
+            LinkVariant::Button => class!("border px-2 align-middle rounded-md"),
         };
+        let class = merge_classes([variant_class, self.class]);
 
         html! {
             a href=(self.route.to_string()) class=(class) {
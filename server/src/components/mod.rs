@@ -1,6 +1,14 @@
+pub use component_macro::Component;
+
 mod cta_button;
 pub use self::cta_button::*;
 
+mod font_faces;
+pub use self::font_faces::*;
+
+mod head;
+pub use self::head::*;
+
 mod image;
 pub use self::image::*;
 
@@ -16,5 +24,11 @@ pub use self::paragraph::*;
 // mod light_dark_image;
 // pub use self::light_dark_image::*;
 
+mod resource_hints;
+pub use self::resource_hints::*;
+
 pub mod stylesheet;
 pub use self::stylesheet::*;
+
+mod svg;
+pub use self::svg::*;
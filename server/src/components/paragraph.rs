@@ -1,32 +1,19 @@
+use crate::components::Component;
+use crate::css_class_groups::merge_classes;
+use ::assets::class;
 use maud::{html, Markup, Render};
 
+#[derive(Component)]
 pub struct Paragraph<'a> {
+    #[component(default)]
     pub class: &'a str,
+    #[component(slot)]
     pub slot: Markup,
 }
 
-impl<'a> Paragraph<'a> {
-    pub fn new() -> Self {
-        Self {
-            class: "",
-            slot: html! { "" },
-        }
-    }
-
-    pub fn slot(mut self, slot: impl Render) -> Self {
-        self.slot = slot.render();
-        self
-    }
-
-    pub fn class(mut self, class: impl Into<&'a str>) -> Self {
-        self.class = class.into();
-        self
-    }
-}
-
 impl Render for Paragraph<'_> {
     fn render(&self) -> Markup {
-        let class = format!("{} mb-grid-2", self.class);
+        let class = merge_classes([class!("mb-grid-2"), self.class]);
 
         html! {
             p class=(class) {
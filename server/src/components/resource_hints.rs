@@ -0,0 +1,30 @@
+use assets::ResourceHint;
+use maud::{html, Markup};
+
+pub fn resource_hints(resource_hints: &[ResourceHint]) -> Markup {
+    html! {
+        @for resource_hint in resource_hints {
+            @match resource_hint {
+                ResourceHint::ModulePreload { href } => {
+                    link rel="modulepreload" href=(href);
+                }
+                ResourceHint::Preload { href, destination, mime_type, crossorigin } => {
+                    link rel="preload" href=(href) as=(destination) type=(mime_type) crossorigin[*crossorigin];
+                }
+                ResourceHint::ImagePreload { href, srcset, sizes, mime_type } => {
+                    link
+                        rel="preload"
+                        as="image"
+                        href=[href]
+                        type=[mime_type]
+                        imagesrcset=(srcset)
+                        imagesizes=(sizes)
+                        fetchpriority="high";
+                }
+                ResourceHint::Preconnect { origin } => {
+                    link rel="preconnect" href=(origin) crossorigin;
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,30 @@
 use assets::CssAsset;
-use maud::{html, Markup};
+use maud::{html, Markup, PreEscaped};
 
+// Small stylesheets are inlined, since fetching them separately would
+// cost a round trip. See `CssAsset::should_inline`.
 pub fn stylesheet(asset: &CssAsset) -> Markup {
+    if asset.should_inline() {
+        return html! {
+            style {
+                (PreEscaped(&asset.contents))
+            }
+        };
+    }
+
     html! {
         link rel="stylesheet" href={(asset.url_path.to_string_lossy())} type="text/css" media="screen";
     }
 }
+
+// Loads a stylesheet without blocking rendering. Browsers download print
+// stylesheets at a low priority without waiting for them, and once it's
+// loaded we switch it to apply to every media type.
+pub fn async_stylesheet(asset: &CssAsset) -> Markup {
+    html! {
+        link rel="stylesheet" href={(asset.url_path.to_string_lossy())} type="text/css" media="print" onload="this.media='all'";
+        noscript {
+            link rel="stylesheet" href={(asset.url_path.to_string_lossy())} type="text/css" media="screen";
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::components::Component;
+use assets::SvgAsset;
+use maud::{html, Markup, PreEscaped, Render};
+
+// Renders an SVG inline, so that it can be styled with CSS and doesn't
+// need a separate request. To load it by URL instead, use the asset's
+// url_path in an img tag.
+#[derive(Component)]
+pub struct Svg<'a> {
+    pub asset: &'a SvgAsset,
+    #[component(default)]
+    pub class: &'a str,
+    /// Overrides the fill on the root element, like "currentColor"
+    /// to match the surrounding text.
+    #[component(default)]
+    pub fill: Option<&'a str>,
+    /// SVGs without a title are hidden from screen readers,
+    /// since they're assumed to be decorative.
+    #[component(default)]
+    pub title: Option<&'a str>,
+}
+
+impl Svg<'_> {
+    fn attributes(&self) -> Vec<(&str, String)> {
+        let mut attributes = vec![
+            ("xmlns", "http://www.w3.org/2000/svg".to_string()),
+            ("viewBox", self.asset.view_box.clone()),
+        ];
+
+        // Root attribute values were escaped at build time.
+        attributes.extend(
+            self.asset
+                .root_attributes
+                .iter()
+                .filter(|(name, _)| name != "class" && !(name == "fill" && self.fill.is_some()))
+                .map(|(name, value)| (name.as_str(), value.clone())),
+        );
+
+        if let Some(fill) = self.fill {
+            attributes.push(("fill", escape(fill)));
+        }
+
+        match self.title {
+            Some(_) => attributes.push(("role", "img".to_string())),
+            None => {
+                attributes.push(("aria-hidden", "true".to_string()));
+                attributes.push(("focusable", "false".to_string()));
+            }
+        }
+
+        attributes
+    }
+}
+
+impl Render for Svg<'_> {
+    fn render(&self) -> Markup {
+        // Maud can't render attributes with dynamic names,
+        // so we write the root element by hand.
+        let mut attributes = String::new();
+        for (name, value) in self.attributes() {
+            attributes.push_str(&format!(" {}=\"{}\"", name, value));
+        }
+
+        let class = format!(
+            "{} {}",
+            self.asset
+                .root_attributes
+                .iter()
+                .find(|(name, _)| name == "class")
+                .map(|(_, class)| class.as_str())
+                .unwrap_or_default(),
+            self.class
+        );
+        let class = class.trim();
+        if !class.is_empty() {
+            attributes.push_str(&format!(" class=\"{}\"", escape(class)));
+        }
+
+        let title = match self.title {
+            Some(title) => html! { title { (title) } }.into_string(),
+            None => String::new(),
+        };
+
+        PreEscaped(format!(
+            "<svg{}>{}{}</svg>",
+            attributes, title, self.asset.inner_markup
+        ))
+    }
+}
+
+fn escape(value: &str) -> String {
+    html! { (value) }.into_string()
+}
@@ -0,0 +1,522 @@
+use crate::assets::ASSETS;
+use crate::routes::*;
+use ::assets::{HasPerformanceBudget, HowCloseToBudget};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Each route renders the same markup on every request, so we only filter
+// the stylesheet once per route. `build_all` fills this in before the
+// server starts handling requests.
+static CRITICAL_CSS: Mutex<Option<HashMap<Route, Arc<CriticalCss>>>> = Mutex::new(None);
+
+/// The rules from our stylesheet that apply to the classes and elements
+/// in a route's page, for inlining in the head. The full stylesheet then
+/// loads asynchronously, without blocking the first render.
+///
+/// This errs on the side of keeping rules, so a rule is kept if some
+/// selector in it only uses classes and elements that are in the page.
+pub fn critical_css(route: Route, page_markup: &str) -> Arc<CriticalCss> {
+    let mut critical_css = CRITICAL_CSS
+        .lock()
+        .expect("Error locking the critical CSS.");
+    let critical_css = critical_css.get_or_insert_with(HashMap::new);
+
+    critical_css
+        .entry(route)
+        .or_insert_with(|| Arc::new(CriticalCss::new(route, page_markup)))
+        .clone()
+}
+
+/// Renders every page once, so that their critical CSS is ready before
+/// the first request, and logs any that are over their performance
+/// budget. The `every_route_is_within_its_critical_css_budget` test is
+/// what enforces the budgets, since panicking here would take the site
+/// down. The full stylesheet's budget is checked when `include_tailwind!`
+/// builds it.
+pub fn build_all() {
+    for route_critical_css in critical_css_for_every_route() {
+        match HowCloseToBudget::new(route_critical_css.as_ref()) {
+            HowCloseToBudget::Below => {}
+            how_close => eprintln!("{}", how_close),
+        }
+    }
+}
+
+fn critical_css_for_every_route() -> Vec<Arc<CriticalCss>> {
+    if ASSETS.css.should_inline() {
+        // Pages inline the whole stylesheet, so there's nothing to split.
+        return vec![];
+    }
+
+    for route in Route::all().filter(Route::register_axum_route) {
+        // Rendering the page calls `critical_css` from the layout.
+        route.html();
+    }
+
+    let critical_css = CRITICAL_CSS
+        .lock()
+        .expect("Error locking the critical CSS.");
+    let mut critical_css: Vec<Arc<CriticalCss>> = critical_css
+        .iter()
+        .flat_map(HashMap::values)
+        .cloned()
+        .collect();
+    critical_css.sort_by(|a, b| a.path.cmp(&b.path));
+    critical_css
+}
+
+pub struct CriticalCss {
+    pub css: String,
+    // Used for reporting, like "critical-css/portfolio".
+    path: PathBuf,
+    load_time_budget: Duration,
+}
+
+impl CriticalCss {
+    fn new(route: Route, page_markup: &str) -> CriticalCss {
+        let used_selectors = UsedSelectors::from_markup(page_markup);
+        let css = filter_rules(&ASSETS.css.contents, &used_selectors);
+        let path = PathBuf::from("critical-css").join(route.to_string().trim_start_matches('/'));
+
+        CriticalCss {
+            css,
+            path,
+            load_time_budget: ASSETS.css.critical_css_load_time_budget,
+        }
+    }
+}
+
+impl HasPerformanceBudget for CriticalCss {
+    fn load_time_budget(&self) -> Duration {
+        self.load_time_budget
+    }
+
+    fn size_in_bytes(&self) -> usize {
+        self.css.len()
+    }
+
+    fn path_for_reporting_asset_over_budget(&self) -> &Path {
+        &self.path
+    }
+}
+
+struct UsedSelectors {
+    classes: BTreeSet<String>,
+    elements: BTreeSet<String>,
+}
+
+impl UsedSelectors {
+    // Maud always quotes attributes with double quotes, so
+    // we don't need a full HTML parser to find them.
+    fn from_markup(markup: &str) -> UsedSelectors {
+        // We're given the body, but rules for the root element apply to it too.
+        let mut elements = BTreeSet::from(["html".to_string()]);
+        let mut classes = BTreeSet::new();
+
+        for tag in markup.split('<').skip(1) {
+            let element: String = tag
+                .chars()
+                .take_while(|character| character.is_ascii_alphanumeric())
+                .collect();
+            if element.is_empty() {
+                // Closing tags, comments, and the doctype.
+                continue;
+            }
+            elements.insert(element.to_ascii_lowercase());
+
+            let tag = tag.split('>').next().unwrap_or_default();
+            if let Some((_, after_class)) = tag.split_once(" class=\"") {
+                let class_attribute = after_class.split('"').next().unwrap_or_default();
+                classes.extend(
+                    class_attribute
+                        .split_whitespace()
+                        .map(unescape_html_attribute),
+                );
+            }
+        }
+
+        UsedSelectors { classes, elements }
+    }
+
+    fn matches(&self, selector_list: &str) -> bool {
+        split_top_level(selector_list, ',').iter().any(|selector| {
+            let (classes, elements) = classes_and_elements_in_selector(selector);
+            classes.iter().all(|class| self.classes.contains(class))
+                && elements
+                    .iter()
+                    .all(|element| self.elements.contains(element))
+        })
+    }
+}
+
+fn unescape_html_attribute(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+// Returns the class names and element names a selector needs,
+// ignoring pseudo classes, attribute selectors, and combinators.
+fn classes_and_elements_in_selector(selector: &str) -> (Vec<String>, Vec<String>) {
+    let characters: Vec<char> = selector.chars().collect();
+    let mut classes = vec![];
+    let mut elements = vec![];
+    let mut index = 0;
+    let mut at_start_of_compound = true;
+
+    let read_identifier = |index: &mut usize| {
+        let mut identifier = String::new();
+        while let Some(&character) = characters.get(*index) {
+            if character == '\\' {
+                if let Some(&escaped) = characters.get(*index + 1) {
+                    identifier.push(escaped);
+                }
+                *index += 2;
+            } else if character.is_alphanumeric() || character == '-' || character == '_' {
+                identifier.push(character);
+                *index += 1;
+            } else {
+                break;
+            }
+        }
+        identifier
+    };
+
+    while let Some(&character) = characters.get(index) {
+        match character {
+            '.' => {
+                index += 1;
+                classes.push(read_identifier(&mut index));
+                at_start_of_compound = false;
+            }
+            ':' => {
+                // Pseudo classes and elements, including any
+                // arguments, like :not(.foo) or ::-webkit-scrollbar.
+                while characters.get(index) == Some(&':') {
+                    index += 1;
+                }
+                read_identifier(&mut index);
+                if characters.get(index) == Some(&'(') {
+                    index = skip_balanced(&characters, index, '(', ')');
+                }
+                at_start_of_compound = false;
+            }
+            '[' => {
+                index = skip_balanced(&characters, index, '[', ']');
+                at_start_of_compound = false;
+            }
+            ' ' | '>' | '+' | '~' | '\n' | '\t' => {
+                index += 1;
+                at_start_of_compound = true;
+            }
+            _ if at_start_of_compound && character.is_alphabetic() => {
+                elements.push(read_identifier(&mut index).to_ascii_lowercase());
+                at_start_of_compound = false;
+            }
+            _ => {
+                // Things like * and #id.
+                index += 1;
+                at_start_of_compound = false;
+            }
+        }
+    }
+
+    (classes, elements)
+}
+
+fn skip_balanced(characters: &[char], start: usize, open: char, close: char) -> usize {
+    let mut depth = 0;
+    let mut index = start;
+
+    while let Some(&character) = characters.get(index) {
+        index += 1;
+        if character == '\\' {
+            index += 1;
+        } else if character == open {
+            depth += 1;
+        } else if character == close {
+            depth -= 1;
+            if depth == 0 {
+                break;
+            }
+        }
+    }
+
+    index
+}
+
+// Splits on a separator, ignoring any inside parentheses,
+// brackets, or strings, like the comma in :is(h1, h2).
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut quote = None;
+    let mut start = 0;
+    let mut escaped = false;
+
+    for (index, character) in text.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+
+        match (character, quote) {
+            ('\\', _) => escaped = true,
+            (_, Some(open_quote)) if character == open_quote => quote = None,
+            (_, Some(_)) => {}
+            ('"' | '\'', None) => quote = Some(character),
+            ('(' | '[', None) => depth += 1,
+            (')' | ']', None) => depth -= 1,
+            (_, None) if character == separator && depth == 0 => {
+                parts.push(&text[start..index]);
+                start = index + character.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(&text[start..]);
+    parts
+}
+
+// A rule or at-rule at the top level of a stylesheet or block.
+enum CssItem<'a> {
+    Statement(&'a str),
+    Block { prelude: &'a str, body: &'a str },
+}
+
+fn parse_items(css: &str) -> Vec<CssItem<'_>> {
+    let bytes = css.as_bytes();
+    let mut items = vec![];
+    let mut index = 0;
+    let mut start = 0;
+    let mut body_start = 0;
+    let mut depth = 0;
+
+    while index < bytes.len() {
+        if let Some(end) = end_of_comment_or_string(css, index) {
+            // Comments between rules aren't worth inlining.
+            if depth == 0 && bytes[index] == b'/' && css[start..index].trim().is_empty() {
+                start = end;
+            }
+            index = end;
+            continue;
+        }
+
+        match bytes[index] {
+            // Escaped characters in selectors, like the quotes in content-\[\'\'\].
+            b'\\' => index += 1,
+            b';' if depth == 0 => {
+                items.push(CssItem::Statement(css[start..=index].trim()));
+                start = index + 1;
+            }
+            b'{' => {
+                if depth == 0 {
+                    body_start = index + 1;
+                }
+                depth += 1;
+            }
+            b'}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    items.push(CssItem::Block {
+                        prelude: css[start..body_start - 1].trim(),
+                        body: &css[body_start..index],
+                    });
+                    start = index + 1;
+                }
+            }
+            _ => {}
+        }
+
+        index += 1;
+    }
+
+    items
+}
+
+// Returns the index just past the comment or string starting at
+// `index`, so that braces and semicolons inside them are ignored.
+fn end_of_comment_or_string(css: &str, index: usize) -> Option<usize> {
+    let bytes = css.as_bytes();
+
+    match bytes[index] {
+        b'/' if bytes.get(index + 1) == Some(&b'*') => Some(
+            css[index + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |end| index + 2 + end + 2),
+        ),
+        quote @ (b'"' | b'\'') => {
+            let mut end = index + 1;
+            while end < bytes.len() && bytes[end] != quote {
+                if bytes[end] == b'\\' {
+                    end += 1;
+                }
+                end += 1;
+            }
+            Some((end + 1).min(bytes.len()))
+        }
+        _ => None,
+    }
+}
+
+fn filter_rules(css: &str, used_selectors: &UsedSelectors) -> String {
+    let mut output = String::new();
+    let mut keyframes = vec![];
+
+    for item in parse_items(css) {
+        match item {
+            CssItem::Statement(statement) => output.push_str(statement),
+            CssItem::Block { prelude, body } if is_keyframes(prelude) => {
+                // We only know which animations are used once
+                // we've filtered everything else.
+                keyframes.push((prelude, body));
+            }
+            CssItem::Block { prelude, body } if is_conditional_group(prelude) => {
+                let filtered_body = filter_rules(body, used_selectors);
+                if !filtered_body.is_empty() {
+                    output.push_str(&format!("{}{{{}}}", prelude, filtered_body));
+                }
+            }
+            CssItem::Block { prelude, body } if prelude.starts_with('@') => {
+                // Other at-rules, like @font-face and @property.
+                output.push_str(&format!("{}{{{}}}", prelude, body));
+            }
+            CssItem::Block { prelude, body } => {
+                if used_selectors.matches(prelude) {
+                    output.push_str(&format!("{}{{{}}}", prelude, body.trim()));
+                }
+            }
+        }
+    }
+
+    for (prelude, body) in keyframes {
+        let name = prelude.split_whitespace().nth(1).unwrap_or_default();
+        if !name.is_empty() && output.contains(name) {
+            output.push_str(&format!("{}{{{}}}", prelude, body));
+        }
+    }
+
+    output
+}
+
+fn is_keyframes(prelude: &str) -> bool {
+    prelude.starts_with("@keyframes") || prelude.starts_with("@-webkit-keyframes")
+}
+
+// At-rules that contain other rules, which we filter recursively.
+fn is_conditional_group(prelude: &str) -> bool {
+    ["@media", "@supports", "@container", "@layer"]
+        .iter()
+        .any(|at_rule| prelude.starts_with(at_rule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn used_selectors(markup: &str) -> UsedSelectors {
+        UsedSelectors::from_markup(markup)
+    }
+
+    #[test]
+    fn every_route_is_within_its_critical_css_budget() {
+        for route_critical_css in critical_css_for_every_route() {
+            println!(
+                "{}: {} bytes",
+                route_critical_css.path.display(),
+                route_critical_css.size_in_bytes()
+            );
+
+            let how_close = HowCloseToBudget::new(route_critical_css.as_ref());
+            assert!(
+                !matches!(how_close, HowCloseToBudget::Over { .. }),
+                "{}",
+                how_close
+            );
+        }
+    }
+
+    #[test]
+    fn unescapes_tailwind_class_names_in_selectors() {
+        let (classes, elements) = classes_and_elements_in_selector(r".md\:w-grid-52");
+        assert_eq!(classes, vec!["md:w-grid-52"]);
+        assert!(elements.is_empty());
+
+        let (classes, elements) =
+            classes_and_elements_in_selector(r".before\:content-\[\'\'\]::before");
+        assert_eq!(classes, vec!["before:content-['']"]);
+        assert!(elements.is_empty());
+    }
+
+    #[test]
+    fn ignores_classes_inside_pseudo_class_arguments() {
+        let (classes, elements) = classes_and_elements_in_selector("ul > li.a:not(.x) + p");
+        assert_eq!(classes, vec!["a"]);
+        assert_eq!(elements, vec!["ul", "li", "p"]);
+
+        let css = ".a:not(.x){color:red}";
+        assert_eq!(filter_rules(css, &used_selectors(r#"<p class="a">"#)), css);
+    }
+
+    #[test]
+    fn splits_only_on_top_level_separators() {
+        assert_eq!(
+            split_top_level(r#":is(h1, h2), [data-a="b,c"], .d\,e, .f"#, ','),
+            vec![":is(h1, h2)", r#" [data-a="b,c"]"#, r" .d\,e", " .f"]
+        );
+    }
+
+    #[test]
+    fn keeps_used_rules_inside_media_queries() {
+        let css = r"@media (min-width: 768px){.md\:w-grid-52{width:1px}.unused{color:red}}@media print{.unused{color:red}}";
+        let markup = r#"<div class="md:w-grid-52">"#;
+
+        assert_eq!(
+            filter_rules(css, &used_selectors(markup)),
+            r"@media (min-width: 768px){.md\:w-grid-52{width:1px}}"
+        );
+    }
+
+    #[test]
+    fn keeps_keyframes_only_for_kept_animations() {
+        let css = "@keyframes spin{to{transform:rotate(360deg)}}@keyframes ping{75%,100%{opacity:0}}.animate-spin{animation:spin 1s linear infinite}.animate-ping{animation:ping 1s infinite}";
+        let markup = r#"<div class="animate-spin">"#;
+
+        assert_eq!(
+            filter_rules(css, &used_selectors(markup)),
+            ".animate-spin{animation:spin 1s linear infinite}@keyframes spin{to{transform:rotate(360deg)}}"
+        );
+    }
+
+    #[test]
+    fn ignores_braces_in_comments_and_strings() {
+        let css = r#"/* .a { */.a{content:"}"}.b{/* } */color:red}.c{content:'{'}"#;
+        let items = parse_items(css);
+
+        let blocks: Vec<(&str, &str)> = items
+            .iter()
+            .map(|item| match item {
+                CssItem::Block { prelude, body } => (*prelude, *body),
+                CssItem::Statement(statement) => panic!("Unexpected statement {statement}"),
+            })
+            .collect();
+        assert_eq!(
+            blocks,
+            vec![
+                (".a", r#"content:"}""#),
+                (".b", "/* } */color:red"),
+                (".c", "content:'{'"),
+            ]
+        );
+
+        assert_eq!(
+            filter_rules(css, &used_selectors(r#"<p class="a c">"#)),
+            r#".a{content:"}"}.c{content:'{'}"#
+        );
+    }
+}
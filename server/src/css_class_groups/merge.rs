@@ -0,0 +1,657 @@
+use shared::design_tokens::colors;
+use std::collections::HashSet;
+
+/// Joins lists of Tailwind classes, dropping any class that a later one
+/// overrides. Components pass their defaults first and the caller's
+/// classes last, so `.class("absolute")` replaces a default `relative`
+/// instead of needing `!absolute` to win.
+///
+/// Classes only conflict when they have the same variants, so `w-grid-52`
+/// doesn't replace `md:w-grid-62`. Classes we don't recognize are kept.
+pub fn merge_classes<'a>(class_lists: impl IntoIterator<Item = &'a str>) -> String {
+    let classes: Vec<&str> = class_lists
+        .into_iter()
+        .flat_map(|class_list| class_list.split_whitespace())
+        .collect();
+
+    // Later classes win, so we go backwards and skip any class
+    // whose group has already been claimed.
+    let mut claimed_groups = HashSet::new();
+    let mut kept_classes = vec![];
+
+    for class in classes.into_iter().rev() {
+        let Some(key) = ConflictKey::from_class(class) else {
+            kept_classes.push(class);
+            continue;
+        };
+
+        if claimed_groups.contains(&key.in_group(&key.group)) {
+            continue;
+        }
+
+        for group in conflicting_groups(&key.group)
+            .iter()
+            .map(|group| group.to_string())
+            .chain([key.group.clone()])
+        {
+            claimed_groups.insert(key.in_group(&group));
+        }
+
+        kept_classes.push(class);
+    }
+
+    kept_classes.reverse();
+
+    let mut seen_classes = HashSet::new();
+    kept_classes.retain(|class| seen_classes.insert(*class));
+    kept_classes.join(" ")
+}
+
+// Two classes conflict if they're in the same group with the same
+// variants, like `hover:` and `before:`, and the same importance.
+struct ConflictKey {
+    variants: String,
+    important: bool,
+    group: String,
+}
+
+impl ConflictKey {
+    fn from_class(class: &str) -> Option<ConflictKey> {
+        let mut parts = split_outside_brackets(class, ':');
+        let utility = parts.pop()?;
+        // The order of variants doesn't change what they do.
+        parts.sort_unstable();
+
+        let (utility, important) = match utility.strip_prefix('!') {
+            Some(utility) => (utility, true),
+            None => (utility, false),
+        };
+
+        Some(ConflictKey {
+            variants: parts.join(":"),
+            important,
+            group: class_group(utility)?,
+        })
+    }
+
+    fn in_group(&self, group: &str) -> String {
+        format!("{}|{}|{}", self.variants, self.important, group)
+    }
+}
+
+// Splits on a separator, ignoring any inside arbitrary
+// values, like the colon in `[background:red]`.
+fn split_outside_brackets(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (index, character) in text.char_indices() {
+        match character {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth -= 1,
+            _ if character == separator && depth == 0 => {
+                parts.push(&text[start..index]);
+                start = index + character.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(&text[start..]);
+    parts
+}
+
+// Utilities that are a single keyword, grouped by the CSS property they set.
+static KEYWORD_GROUPS: &[(&str, &[&str])] = &[
+    (
+        "position",
+        &["static", "fixed", "absolute", "relative", "sticky"],
+    ),
+    (
+        "display",
+        &[
+            "block",
+            "inline-block",
+            "inline",
+            "flex",
+            "inline-flex",
+            "grid",
+            "inline-grid",
+            "contents",
+            "flow-root",
+            "list-item",
+            "table",
+            "hidden",
+        ],
+    ),
+    ("visibility", &["visible", "invisible", "collapse"]),
+    (
+        "text-decoration-line",
+        &["underline", "overline", "line-through", "no-underline"],
+    ),
+    ("font-style", &["italic", "not-italic"]),
+    (
+        "text-transform",
+        &["uppercase", "lowercase", "capitalize", "normal-case"],
+    ),
+    ("isolation", &["isolate", "isolation-auto"]),
+    ("box-sizing", &["box-border", "box-content"]),
+];
+
+// Utilities that take a value, longest prefixes first so
+// that `border-x-2` isn't mistaken for `border-*`.
+static PREFIXES: &[&str] = &[
+    "min-w",
+    "min-h",
+    "max-w",
+    "max-h",
+    "w",
+    "h",
+    "size",
+    "px",
+    "py",
+    "ps",
+    "pe",
+    "pt",
+    "pr",
+    "pb",
+    "pl",
+    "p",
+    "mx",
+    "my",
+    "ms",
+    "me",
+    "mt",
+    "mr",
+    "mb",
+    "ml",
+    "m",
+    "inset-x",
+    "inset-y",
+    "inset",
+    "start",
+    "end",
+    "top",
+    "right",
+    "bottom",
+    "left",
+    "z",
+    "order",
+    "gap-x",
+    "gap-y",
+    "gap",
+    "space-x",
+    "space-y",
+    "grid-cols",
+    "grid-rows",
+    "grid-flow",
+    "col-span",
+    "col-start",
+    "col-end",
+    "row-span",
+    "row-start",
+    "row-end",
+    "basis",
+    "grow",
+    "shrink",
+    "flex",
+    "justify-items",
+    "justify-self",
+    "justify",
+    "items",
+    "self",
+    "content",
+    "place-content",
+    "place-items",
+    "place-self",
+    "overflow-x",
+    "overflow-y",
+    "overflow",
+    "whitespace",
+    "break",
+    "text",
+    "font",
+    "leading",
+    "tracking",
+    "align",
+    "underline-offset",
+    "decoration",
+    "bg",
+    "rounded-tl",
+    "rounded-tr",
+    "rounded-br",
+    "rounded-bl",
+    "rounded-t",
+    "rounded-r",
+    "rounded-b",
+    "rounded-l",
+    "rounded",
+    "border-x",
+    "border-y",
+    "border-t",
+    "border-r",
+    "border-b",
+    "border-l",
+    "border",
+    "ring-offset",
+    "ring",
+    "outline-offset",
+    "outline",
+    "shadow",
+    "opacity",
+    "object",
+    "origin",
+    "scale-x",
+    "scale-y",
+    "scale",
+    "rotate",
+    "translate-x",
+    "translate-y",
+    "skew-x",
+    "skew-y",
+    "transition",
+    "duration",
+    "ease",
+    "delay",
+    "animate",
+    "cursor",
+    "select",
+    "pointer-events",
+    "fill",
+    "stroke",
+    "aspect",
+    "columns",
+    "blur",
+    "brightness",
+    "backdrop-blur",
+];
+
+// The group a utility belongs to, or None if we don't recognize it.
+fn class_group(utility: &str) -> Option<String> {
+    // Negative values, like -top-grid-20, are in the same group as positive ones.
+    let utility = utility.strip_prefix('-').unwrap_or(utility);
+
+    // Arbitrary properties, like [background:var(--placeholder)].
+    if let Some(property) = utility
+        .strip_prefix('[')
+        .and_then(|utility| utility.split_once(':'))
+        .map(|(property, _)| property)
+    {
+        return Some(format!("[{}]", property));
+    }
+
+    if let Some((group, _)) = KEYWORD_GROUPS
+        .iter()
+        .find(|(_, keywords)| keywords.contains(&utility))
+    {
+        return Some(group.to_string());
+    }
+
+    let (prefix, value) = PREFIXES.iter().find_map(|prefix| {
+        if utility == *prefix {
+            Some((*prefix, ""))
+        } else {
+            utility
+                .strip_prefix(prefix)
+                .and_then(|rest| rest.strip_prefix('-'))
+                .map(|value| (*prefix, value))
+        }
+    })?;
+
+    let group = match prefix {
+        "text" if is_color(value) => "text-color",
+        "text" if ["left", "center", "right", "justify", "start", "end"].contains(&value) => {
+            "text-align"
+        }
+        "text" if ["ellipsis", "clip"].contains(&value) => "text-overflow",
+        "text" if ["wrap", "nowrap", "balance", "pretty"].contains(&value) => "text-wrap",
+        "text" => "font-size",
+
+        "font" if is_font_weight(value) => "font-weight",
+        "font" => "font-family",
+
+        "bg" if is_color(value) => "bg-color",
+        "bg" if ["fixed", "local", "scroll"].contains(&value) => "bg-attachment",
+        "bg" if ["auto", "cover", "contain"].contains(&value) => "bg-size",
+        "bg" if value.starts_with("repeat") || value == "no-repeat" => "bg-repeat",
+        "bg" if value == "none" || value.starts_with("gradient") || value.starts_with("[url(") => {
+            "bg-image"
+        }
+        "bg" if value.starts_with('[') => "bg-color",
+        "bg" => "bg-position",
+
+        "border" | "border-x" | "border-y" | "border-t" | "border-r" | "border-b" | "border-l" => {
+            return Some(
+                if ["solid", "dashed", "dotted", "double", "hidden", "none"].contains(&value) {
+                    "border-style".to_string()
+                } else if ["collapse", "separate"].contains(&value) {
+                    "border-collapse".to_string()
+                } else if is_color(value) {
+                    format!("{}-color", prefix)
+                } else {
+                    format!("{}-width", prefix)
+                },
+            );
+        }
+
+        "ring" if value == "inset" => "ring-inset",
+        "ring" if is_color(value) => "ring-color",
+        "ring" => "ring-width",
+        "ring-offset" if is_color(value) => "ring-offset-color",
+        "outline" if ["", "none", "dashed", "dotted", "double"].contains(&value) => "outline-style",
+        "outline" if is_color(value) => "outline-color",
+        "outline" => "outline-width",
+
+        "decoration" if ["solid", "double", "dotted", "dashed", "wavy"].contains(&value) => {
+            "decoration-style"
+        }
+        "decoration" if is_color(value) => "decoration-color",
+        "decoration" => "decoration-thickness",
+
+        "shadow" if is_color(value) => "shadow-color",
+        "stroke" if !is_color(value) => "stroke-width",
+
+        "object" if ["contain", "cover", "fill", "none", "scale-down"].contains(&value) => {
+            "object-fit"
+        }
+        "object" => "object-position",
+
+        "flex" if ["row", "row-reverse", "col", "col-reverse"].contains(&value) => "flex-direction",
+        "flex" if ["wrap", "wrap-reverse", "nowrap"].contains(&value) => "flex-wrap",
+
+        "content" if value == "none" || value.starts_with('[') => "content",
+        "content" => "align-content",
+
+        _ => prefix,
+    };
+
+    Some(group.to_string())
+}
+
+// Groups that a group overrides, like `p-grid-2` overriding `px-grid-4`.
+fn conflicting_groups(group: &str) -> &'static [&'static str] {
+    match group {
+        "p" => &["px", "py", "ps", "pe", "pt", "pr", "pb", "pl"],
+        "px" => &["pr", "pl"],
+        "py" => &["pt", "pb"],
+        "m" => &["mx", "my", "ms", "me", "mt", "mr", "mb", "ml"],
+        "mx" => &["mr", "ml"],
+        "my" => &["mt", "mb"],
+        "inset" => &[
+            "inset-x", "inset-y", "start", "end", "top", "right", "bottom", "left",
+        ],
+        "inset-x" => &["right", "left"],
+        "inset-y" => &["top", "bottom"],
+        "size" => &["w", "h"],
+        "gap" => &["gap-x", "gap-y"],
+        "overflow" => &["overflow-x", "overflow-y"],
+        "flex" => &["basis", "grow", "shrink"],
+        "scale" => &["scale-x", "scale-y"],
+        // Font sizes set a line height too.
+        "font-size" => &["leading"],
+        "rounded" => &[
+            "rounded-t",
+            "rounded-r",
+            "rounded-b",
+            "rounded-l",
+            "rounded-tl",
+            "rounded-tr",
+            "rounded-br",
+            "rounded-bl",
+        ],
+        "rounded-t" => &["rounded-tl", "rounded-tr"],
+        "rounded-r" => &["rounded-tr", "rounded-br"],
+        "rounded-b" => &["rounded-br", "rounded-bl"],
+        "rounded-l" => &["rounded-tl", "rounded-bl"],
+        "border-width" => &[
+            "border-x-width",
+            "border-y-width",
+            "border-t-width",
+            "border-r-width",
+            "border-b-width",
+            "border-l-width",
+        ],
+        "border-x-width" => &["border-r-width", "border-l-width"],
+        "border-y-width" => &["border-t-width", "border-b-width"],
+        "border-color" => &[
+            "border-x-color",
+            "border-y-color",
+            "border-t-color",
+            "border-r-color",
+            "border-b-color",
+            "border-l-color",
+        ],
+        "border-x-color" => &["border-r-color", "border-l-color"],
+        "border-y-color" => &["border-t-color", "border-b-color"],
+        // Like the placeholders in the Image component.
+        "[background]" => &[
+            "bg-color",
+            "bg-image",
+            "bg-position",
+            "bg-size",
+            "bg-repeat",
+            "bg-attachment",
+        ],
+        // Strictly a background color only replaces part of a background
+        // shorthand, but a caller setting one wants a plain background.
+        "bg-color" => &["[background]"],
+        _ => &[],
+    }
+}
+
+fn is_color(value: &str) -> bool {
+    if let Some(arbitrary_value) = value
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'))
+    {
+        return arbitrary_value.starts_with('#')
+            || arbitrary_value.starts_with("color:")
+            || arbitrary_value.starts_with("rgb")
+            || arbitrary_value.starts_with("hsl")
+            || arbitrary_value.starts_with("theme(colors.");
+    }
+
+    // Colors can have an opacity, like bg-slate/50.
+    let value = value.split('/').next().unwrap_or(value);
+
+    if ["inherit", "current", "transparent", "black", "white"].contains(&value) {
+        return true;
+    }
+
+    if colors::all.iter().any(|(name, _)| *name == value) {
+        return true;
+    }
+
+    if let Some(petal_color) = value.strip_prefix("petal-") {
+        return colors::petal::all
+            .iter()
+            .any(|(name, _)| *name == petal_color);
+    }
+
+    // Tailwind's default palette, like red-500.
+    match value.rsplit_once('-') {
+        Some((name, shade)) => {
+            TAILWIND_COLOR_NAMES.contains(&name) && shade.chars().all(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+static TAILWIND_COLOR_NAMES: &[&str] = &[
+    "slate", "gray", "zinc", "neutral", "stone", "red", "orange", "amber", "yellow", "lime",
+    "green", "emerald", "teal", "cyan", "sky", "blue", "indigo", "violet", "purple", "fuchsia",
+    "pink", "rose",
+];
+
+fn is_font_weight(value: &str) -> bool {
+    [
+        "thin",
+        "extralight",
+        "light",
+        "normal",
+        "medium",
+        "semibold",
+        "bold",
+        "extrabold",
+        "black",
+    ]
+    .contains(&value)
+        || value
+            .strip_prefix('[')
+            .is_some_and(|value| value.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn later_classes_win() {
+        assert_eq!(merge_classes(["relative", "absolute"]), "absolute");
+        assert_eq!(
+            merge_classes(["w-grid-32 h-grid-32", "w-grid-26"]),
+            "h-grid-32 w-grid-26"
+        );
+        assert_eq!(merge_classes(["mb-grid-2", "mb-grid-2"]), "mb-grid-2");
+    }
+
+    #[test]
+    fn keeps_unrecognized_classes() {
+        assert_eq!(merge_classes(["flower", "hero flower"]), "flower hero");
+    }
+
+    #[test]
+    fn matches_the_longest_prefix() {
+        // `w` is a prefix of `whitespace`, but they're different utilities.
+        assert_eq!(
+            merge_classes(["w-grid-52", "whitespace-nowrap"]),
+            "w-grid-52 whitespace-nowrap"
+        );
+        assert_eq!(
+            merge_classes(["whitespace-normal", "whitespace-nowrap"]),
+            "whitespace-nowrap"
+        );
+        assert_eq!(merge_classes(["border-x-2", "border-4"]), "border-4");
+        assert_eq!(
+            merge_classes(["border-4", "border-x-2"]),
+            "border-4 border-x-2"
+        );
+    }
+
+    #[test]
+    fn shorthands_override_their_sides() {
+        assert_eq!(merge_classes(["pl-grid-2", "px-grid-4"]), "px-grid-4");
+        assert_eq!(
+            merge_classes(["px-grid-4", "pl-grid-2"]),
+            "px-grid-4 pl-grid-2"
+        );
+        assert_eq!(merge_classes(["px-grid-4 py-grid-2", "p-0"]), "p-0");
+    }
+
+    #[test]
+    fn variants_only_conflict_with_the_same_variants() {
+        assert_eq!(
+            merge_classes(["md:w-grid-62", "w-grid-52"]),
+            "md:w-grid-62 w-grid-52"
+        );
+        assert_eq!(
+            merge_classes(["md:hover:w-1", "hover:md:w-2"]),
+            "hover:md:w-2"
+        );
+        assert_eq!(
+            merge_classes(["before:content-['']", "before:content-['*']"]),
+            "before:content-['*']"
+        );
+    }
+
+    #[test]
+    fn important_only_conflicts_with_important() {
+        assert_eq!(merge_classes(["!w-1", "w-2"]), "!w-1 w-2");
+        assert_eq!(merge_classes(["!w-1", "!w-2"]), "!w-2");
+        assert_eq!(merge_classes(["hover:!w-1", "hover:!w-2"]), "hover:!w-2");
+    }
+
+    #[test]
+    fn negative_values_conflict_with_positive_ones() {
+        assert_eq!(merge_classes(["top-0", "-top-grid-20"]), "-top-grid-20");
+        assert_eq!(
+            merge_classes(["before:-top-grid-20", "top-0"]),
+            "before:-top-grid-20 top-0"
+        );
+    }
+
+    #[test]
+    fn arbitrary_properties_conflict_by_property() {
+        assert_eq!(
+            merge_classes(["[background:red]", "[background:var(--placeholder)]"]),
+            "[background:var(--placeholder)]"
+        );
+        assert_eq!(
+            merge_classes(["[mask:none]", "[background:red]"]),
+            "[mask:none] [background:red]"
+        );
+    }
+
+    #[test]
+    fn background_colors_override_background_shorthands() {
+        assert_eq!(
+            merge_classes(["[background:var(--placeholder)]", "bg-slate"]),
+            "bg-slate"
+        );
+        assert_eq!(
+            merge_classes(["bg-slate", "[background:var(--placeholder)]"]),
+            "[background:var(--placeholder)]"
+        );
+        assert_eq!(
+            merge_classes(["bg-[var(--placeholder)]", "bg-petal-pink/50"]),
+            "bg-petal-pink/50"
+        );
+        assert_eq!(
+            merge_classes(["bg-slate", "bg-cover bg-center"]),
+            "bg-slate bg-cover bg-center"
+        );
+    }
+
+    // The classes the Tooth component merges for the last tooth.
+    #[test]
+    fn last_tooth_is_smaller() {
+        let figure_class = merge_classes([
+            "absolute top-0 w-grid-96 h-grid-64 grid grid-cols-3 grid-rows-2 z-10",
+            "before:content-[''] before:absolute before:h-grid-20 before:w-grid-20",
+            "before:-top-grid-20 before:right-0 before:rounded-br-tooth",
+            "right-0",
+            "w-grid-78 h-grid-52",
+        ]);
+
+        assert_eq!(
+            figure_class,
+            "absolute top-0 grid grid-cols-3 grid-rows-2 z-10 \
+             before:content-[''] before:absolute before:h-grid-20 before:w-grid-20 \
+             before:-top-grid-20 before:right-0 before:rounded-br-tooth \
+             right-0 w-grid-78 h-grid-52"
+        );
+
+        let block_class = merge_classes([
+            "w-grid-32 h-grid-32 ring-1 ring-slate",
+            "w-grid-26 h-grid-26",
+            "ring-0",
+        ]);
+
+        assert_eq!(block_class, "ring-slate w-grid-26 h-grid-26 ring-0");
+    }
+
+    // ProjectImage passes bg-slate to an Image with a placeholder.
+    #[test]
+    fn project_image_background_replaces_the_placeholder() {
+        let project_image_class =
+            "object-contain object-bottom bg-slate z-0 w-grid-52 h-grid-52 left-0 self-start";
+
+        for placeholder_class in ["bg-[var(--placeholder)]", "[background:var(--placeholder)]"] {
+            assert_eq!(
+                merge_classes(["select-none", placeholder_class, project_image_class]),
+                format!("select-none {}", project_image_class)
+            );
+        }
+    }
+}
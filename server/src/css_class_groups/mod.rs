@@ -1,3 +1,6 @@
+mod merge;
+pub use self::merge::*;
+
 pub fn bg_background() -> &'static str {
     ""
 }
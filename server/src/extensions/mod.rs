@@ -1,5 +1,2 @@
 mod markup;
 pub use self::markup::*;
-
-mod path_buf;
-pub use self::path_buf::*;
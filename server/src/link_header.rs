@@ -0,0 +1,64 @@
+use crate::routes::*;
+use assets::ResourceHint;
+use axum::http::HeaderValue;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// Each route renders the same markup on every request, so its Link header
+// is the same too. `layout()` records it the first time it renders a route.
+static LINK_HEADERS: Mutex<Option<HashMap<Route, HeaderValue>>> = Mutex::new(None);
+
+/// Records the value of the `Link` header to send with a route's page, so
+/// that the browser can start fetching what's in it before the body
+/// arrives. Hyper can't send informational responses yet, so we can't
+/// send these as 103 Early Hints ourselves, but CDNs like Cloudflare turn
+/// Link headers into 103 Early Hints for the next request.
+pub fn record_link_header(route: Route, resource_hints: &[ResourceHint]) {
+    let mut link_headers = LINK_HEADERS
+        .lock()
+        .expect("Error locking the Link headers.");
+    let link_headers = link_headers.get_or_insert_with(HashMap::new);
+
+    link_headers.entry(route).or_insert_with(|| {
+        let link_header = resource_hints
+            .iter()
+            .map(ResourceHint::to_link_header_value)
+            .collect::<Vec<_>>()
+            .join(", ");
+        HeaderValue::from_str(&link_header).expect("Error creating Link header.")
+    });
+}
+
+/// The Link header for a route, if its page has been rendered with `layout()`.
+pub fn link_header(route: Route) -> Option<HeaderValue> {
+    LINK_HEADERS
+        .lock()
+        .expect("Error locking the Link headers.")
+        .as_ref()?
+        .get(&route)
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::ASSETS;
+
+    #[test]
+    fn link_header_matches_the_rendered_page() {
+        let page = Route::Portfolio.html().0;
+        let link_header =
+            link_header(Route::Portfolio).expect("Portfolio should have a Link header.");
+        let link_header = link_header.to_str().unwrap();
+
+        // The largest contentful paint image is preloaded in both.
+        assert!(page.contains(r#"rel="preload" as="image""#));
+        assert!(link_header.contains("rel=preload; as=image; imagesrcset="));
+
+        let stylesheet_url = ASSETS.css.url_path.to_string_lossy().to_string();
+        assert_eq!(
+            link_header.contains(&stylesheet_url),
+            !ASSETS.css.should_inline()
+        );
+    }
+}
@@ -4,8 +4,10 @@ use routes::*;
 use tower_http::services::ServeDir;
 
 mod components;
+mod critical_css;
 mod css_class_groups;
 mod extensions;
+mod link_header;
 // mod notion;
 mod assets;
 mod routes;
@@ -18,7 +20,13 @@ mod side;
 //     Ok(response)
 // }
 
-use axum::{extract::Request, routing::get, Router};
+use axum::{
+    extract::Request,
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
 
 #[tokio::main]
 async fn main() {
@@ -37,6 +45,8 @@ async fn main() {
             ServeDir::new(built_assets_dir),
         );
 
+    critical_css::build_all();
+
     let port = std::env::var("PORT").unwrap_or_else(|_| "3000".to_string());
     let host_and_port = format!("0.0.0.0:{}", port);
     // Run our app with hyper, listening globally on the specified port.
@@ -45,9 +55,16 @@ async fn main() {
 }
 
 // For now, all of our routes return HTML.
-async fn handle_request(req: Request) -> axum::response::Html<String> {
+async fn handle_request(req: Request) -> Response {
     let route = Route::from_request(&req);
-    route.html().into_axum_html_response()
+    let mut response = route.html().into_axum_html_response().into_response();
+
+    // Rendering the page records its Link header.
+    if let Some(link_header) = link_header::link_header(route) {
+        response.headers_mut().insert(header::LINK, link_header);
+    }
+
+    response
 }
 
 async fn health_check() {}
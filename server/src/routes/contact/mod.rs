@@ -1,3 +1,4 @@
+use ::assets::class;
 use maud::{html, Markup};
 
 // pub mod form_submission;
@@ -9,7 +10,7 @@ pub fn contact_page() -> Markup {
         p { "O hai mark" }
 
         form
-            class="flex flex-col gap-2 w-full max-w-2xl py-4"
+            class=(class!("flex flex-col gap-2 w-full max-w-2xl py-4"))
             action="http://localhost:3000/contact"
             method="POST"
         {
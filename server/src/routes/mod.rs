@@ -1,5 +1,7 @@
 use crate::components::*;
+use ::assets::class;
 use maud::{html, Markup};
+use shared::design_tokens;
 
 mod build_time;
 pub use self::build_time::*;
@@ -19,6 +21,7 @@ mod tooth;
 
 pub fn home_page() -> Markup {
     layout(
+        Route::Home,
         "rainsound.ai: Custom AI & Web App Development",
         html! {
             (hero_section())
@@ -29,43 +32,43 @@ pub fn home_page() -> Markup {
 
 fn hero_section() -> Markup {
     html! {
-        div class="hero h-grid-108 z-10 relative flex items-center justify-center w-full" {
-            div class="flex flex-col w-grid-128" {
-                h1 class="text-left font-fugi" {
+        div class=(class!("hero h-grid-108 z-10 relative flex items-center justify-center w-full")) {
+            div class=(class!("flex flex-col w-grid-128")) {
+                h1 class=(class!("text-left font-fugi")) {
                     "We're an AI"
                 }
-                h1 class="text-center font-fugi" {
+                h1 class=(class!("text-center font-fugi")) {
                     "Technology Studio"
                 }
-                h4 class="text-right font-fugi" {
+                h4 class=(class!("text-right font-fugi")) {
                     "We can help transform your vision into a beautifully crafted product"
                 }
             }
 
-            (Flower::new().class("!absolute top-grid-36 left-0 scale-[80%]"))
-            (Flower::new().class("!absolute top-grid-24 right-grid-16 scale-[200%] rotate-180"))
-            (Flower::new().class("!absolute bottom-grid-24 left-grid-24 scale-[130%] rotate-90"))
-            (Flower::new().class("!absolute bottom-grid-20 right-grid-40 scale-[40%] rotate-45"))
+            (Flower::new().class(class!("absolute top-grid-36 left-0 scale-[80%]")))
+            (Flower::new().class(class!("absolute top-grid-24 right-grid-16 scale-[200%] rotate-180")))
+            (Flower::new().class(class!("absolute bottom-grid-24 left-grid-24 scale-[130%] rotate-90")))
+            (Flower::new().class(class!("absolute bottom-grid-20 right-grid-40 scale-[40%] rotate-45")))
         }
     }
 }
 
 fn body() -> Markup {
-    let h2_class = "mb-grid-4";
+    let h2_class = class!("mb-grid-4");
 
     html! {
-        div class="flex flex-col rounded-tooth w-full" style="background: linear-gradient(to bottom, #e189dc 0%, #e189ab 25%, #e1a889 50%, #e2d989 100%);" {
+        div class=(class!("flex flex-col rounded-tooth w-full")) style={ "background: " (design_tokens::petal_gradient()) ";" } {
             (Row::left()
                 .slot(html! {
-                    h2 class={ (h2_class) " pt-grid-4" } {
+                    h2 class={ (h2_class) " " (class!("pt-grid-4")) } {
                         "Who We Are"
                     }
                     (Paragraph::new()
-                        .class("w-grid-62")
+                        .class(class!("w-grid-62"))
                         .slot("We're a technology studio with expertise in machine learning, AI engineering, and unreasonably effective tools like Rust.")
                     )
                     (Paragraph::new()
-                        .class("w-grid-62")
+                        .class(class!("w-grid-62"))
                         .slot("We love to build beautiful products for industry leaders and early-to-mid stage startups.")
                     )
                 })
@@ -77,7 +80,7 @@ fn body() -> Markup {
                         "How We Can Help"
                     }
                     (Paragraph::new()
-                        .class("w-grid-52")
+                        .class(class!("w-grid-52"))
                         .slot("We can supercharge your business with cutting-edge AI solutions and application development.")
                     )
                     (Paragraph::new()
@@ -92,16 +95,16 @@ fn body() -> Markup {
                         "Build Something With Us"
                     }
                     (Paragraph::new()
-                        .class("w-grid-50")
+                        .class(class!("w-grid-50"))
                         .slot("Got an idea for an AI-related project? We offer complimentary solution architecture and design! Work with us to bring your vision to life.")
                     )
                     (Paragraph::new()
-                        .class("w-grid-50")
+                        .class(class!("w-grid-50"))
                         .slot(html! {
                             "See some examples of our work "
                             (Link::button(Route::Portfolio)
                                 .slot(html! {
-                                    span class="inline-block translate-y-[0.1vw]" {
+                                    span class=(class!("inline-block translate-y-[0.1vw]")) {
                                         "here"
                                     }
                                 })
@@ -119,11 +122,11 @@ fn body() -> Markup {
                         "What's Your Vision?"
                     }
 
-                    div class="inline-block w-grid-64 mt-grid-10 text-center float-right" {
+                    div class=(class!("inline-block w-grid-64 mt-grid-10 text-center float-right")) {
                         (Link::no_underline(Route::Contact)
                             .slot(CtaButton::new()
                                 .slot(html! {
-                                    span class="inline-block translate-y-[0.2vw]" {
+                                    span class=(class!("inline-block translate-y-[0.2vw]")) {
                                         "Tell us about it!"
                                     }
                                 })
@@ -3,6 +3,7 @@ use maud::{html, Markup};
 
 pub fn not_found_page() -> Markup {
     layout(
+        Route::NotFound,
         "rainsound.ai: Page Not Found",
         html! {
             "Couldn't find that thing you were looking for."
@@ -1,6 +1,8 @@
 use crate::components::*;
 use crate::side::*;
+use ::assets::class;
 use maud::{html, Markup};
+use shared::route::Route;
 
 mod projects;
 use projects::*;
@@ -11,6 +13,7 @@ use self::project_image::*;
 
 pub fn portfolio_page() -> Markup {
     layout(
+        Route::Portfolio,
         "rainsound.ai: Past Projects",
         html! {
             @for (index, project) in all_projects().into_iter().enumerate() {
@@ -19,7 +22,7 @@ pub fn portfolio_page() -> Markup {
                 @let project_card = ProjectCard::new(index, side, project);
                 @let project_image = ProjectImage::new(index, side);
 
-                section class="relative flex justify-between w-full" {
+                section class=(class!("relative flex justify-between w-full")) {
                     @match side {
                         Left => {
                             (project_card)
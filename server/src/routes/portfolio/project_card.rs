@@ -1,6 +1,7 @@
 use super::projects::*;
 use crate::components::*;
 use crate::side::*;
+use ::assets::class;
 use maud::{html, Markup, Render};
 use shared::route::Route;
 
@@ -21,57 +22,63 @@ impl Render for ProjectCard {
 
         let (side_before, side_after) = match side {
             Left => (
-                "before:left-0 before:bottom-full before:rounded-br-tooth before:shadow-[0_calc(10_*_100vw_/_180)_0_0_#283036]",
-                "after:right-full after:bottom-0 after:rounded-br-tooth after:shadow-[0_calc(10_*_100vw_/_180)_0_0_#283036]",
+                class!("before:left-0 before:bottom-full before:rounded-br-tooth before:shadow-[0_theme(borderRadius.tooth)_0_0_theme(colors.slate)]"),
+                class!("after:right-full after:bottom-0 after:rounded-br-tooth after:shadow-[0_theme(borderRadius.tooth)_0_0_theme(colors.slate)]"),
             ),
             Right => (
-                "before:left-full before:top-0 before:rounded-tl-tooth before:shadow-[0_calc(-10_*_100vw_/_180)_0_0_#283036]",
-                "after:left-0 after:top-full after:rounded-tl-tooth after:shadow-[0_calc(-10_*_100vw_/_180)_0_0_#283036]",
+                class!("before:left-full before:top-0 before:rounded-tl-tooth before:shadow-[0_calc(-1_*_theme(borderRadius.tooth))_0_0_theme(colors.slate)]"),
+                class!("after:left-0 after:top-full after:rounded-tl-tooth after:shadow-[0_calc(-1_*_theme(borderRadius.tooth))_0_0_theme(colors.slate)]"),
             ),
         };
-        let corner_before = "before:content-[''] before:absolute before:w-full before:h-full";
-        let corner_after = "after:content-[''] after:absolute after:w-full after:h-full";
+        let corner_before =
+            class!("before:content-[''] before:absolute before:w-full before:h-full");
+        let corner_after = class!("after:content-[''] after:absolute after:w-full after:h-full");
         let before_and_after = format!("{side_before} {side_after} {corner_before} {corner_after}");
 
         let cutout_side = match side {
-            Left => "left bottom-0 right-0",
-            Right => "right top-0 left-0",
+            Left => class!("bottom-0 right-0"),
+            Right => class!("top-0 left-0"),
         };
 
-        let cutout_class =
-            format!("{before_and_after} {cutout_side} bg-slate w-grid-32 h-grid-32 absolute z-10",);
+        let cutout_class = format!(
+            "{before_and_after} {cutout_side} {}",
+            class!("bg-slate w-grid-32 h-grid-32 absolute z-10")
+        );
 
         let maybe_text_right = match side {
             Left => "",
-            Right => "text-right",
+            Right => class!("text-right"),
         };
 
         html! {
             div
                 class={
-                    "relative h-grid-69 w-grid-105 px-grid-5 py-grid-6 flex flex-col odd:self-start even:items-end even:self-end rounded-tooth z-0 "
+                    (class!("relative h-grid-69 w-grid-105 px-grid-5 py-grid-6 flex flex-col odd:self-start even:items-end even:self-end rounded-tooth z-0"))
+                    " "
                     (background_color)
                 }
             {
                 div
                     class={
-                        "text-grid-8 underline font-light "
+                        (class!("text-grid-8 underline font-light"))
+                        " "
                         (maybe_text_right)
                     }
                 {
                     (number)
                 }
 
-                h2 class={ "grow w-grid-69 " (maybe_text_right) } {
+                h2 class={ (class!("grow w-grid-69")) " " (maybe_text_right) } {
                     ( text )
                 }
 
                 div
                     class={
-                        "flex justify-between z-20 "
+                        (class!("flex justify-between z-20"))
+                        " "
                         (match side {
-                            Left => "w-grid-64",
-                            Right => "w-full",
+                            Left => class!("w-grid-64"),
+                            Right => class!("w-full"),
                         })
                     }
                 {
@@ -80,7 +87,7 @@ impl Render for ProjectCard {
                     )
                     (Link::button(Route::Contact)
                         .slot(html! {
-                            span class="inline-block translate-y-[0.2vw]" {
+                            span class=(class!("inline-block translate-y-[0.2vw]")) {
                                 "Build This"
                             }
                         })
@@ -105,12 +112,12 @@ impl ProjectCard {
 
     fn background_color(&self) -> &'static str {
         let mut color_classes = [
-            "bg-petal-lavender",
-            "bg-petal-salmon",
-            "bg-petal-orange",
-            "bg-petal-yellow",
-            "bg-petal-blue",
-            "bg-petal-purple",
+            class!("bg-petal-lavender"),
+            class!("bg-petal-salmon"),
+            class!("bg-petal-orange"),
+            class!("bg-petal-yellow"),
+            class!("bg-petal-blue"),
+            class!("bg-petal-purple"),
         ]
         .into_iter()
         .cycle();
@@ -1,6 +1,7 @@
 use crate::assets::ASSETS;
 use crate::components::*;
 use crate::side::*;
+use ::assets::class;
 use assets::ImageAsset;
 use maud::{html, Markup, Render};
 
@@ -12,23 +13,32 @@ pub struct ProjectImage {
 impl Render for ProjectImage {
     fn render(&self) -> Markup {
         let right_or_left = match self.side {
-            Left => "right-0",
-            Right => "left-0",
+            Left => class!("right-0"),
+            Right => class!("left-0"),
         };
         let align_self = if self.index > 0 {
-            "self-end"
+            class!("self-end")
         } else {
-            "self-start"
+            class!("self-start")
         };
 
         let class = format!(
-            "object-contain object-bottom !bg-slate z-0 w-grid-52 h-grid-52 {right_or_left} {align_self}",
+            "{} {right_or_left} {align_self}",
+            class!("object-contain object-bottom bg-slate z-0 w-grid-52 h-grid-52")
         );
 
+        let image = Image::new(self.asset()).class(&*class);
+
+        // Only the first project is visible when the page loads, and
+        // its image is the biggest thing on screen.
+        let image = if self.index == 0 {
+            image.largest_contentful_paint()
+        } else {
+            image
+        };
+
         html! {
-            (Image::new(self.asset())
-                .class(&*class)
-            )
+            (image)
         }
     }
 }
@@ -39,13 +49,14 @@ impl ProjectImage {
     }
 
     fn asset(&self) -> &ImageAsset {
+        let flowers = &ASSETS.project_flowers;
         let mut image_assets = [
-            &ASSETS.project_flower_one,
-            &ASSETS.project_flower_two,
-            &ASSETS.project_flower_three,
-            &ASSETS.project_flower_four,
-            &ASSETS.project_flower_five,
-            &ASSETS.project_flower_six,
+            &flowers.one,
+            &flowers.two,
+            &flowers.three,
+            &flowers.four,
+            &flowers.five,
+            &flowers.six,
         ]
         .into_iter()
         .cycle();
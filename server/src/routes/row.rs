@@ -1,52 +1,44 @@
 use super::tooth::*;
+use crate::components::Component;
 use crate::side::*;
+use ::assets::class;
 use maud::{html, Markup, Render};
 
+#[derive(Component)]
 pub struct Row {
     pub side: Side,
+    #[component(default)]
     pub last: bool,
+    #[component(slot)]
     pub slot: Markup,
 }
 
 impl Row {
     pub fn left() -> Self {
-        Self {
-            side: Left,
-            slot: html! { "" },
-            last: false,
-        }
+        Self::new(Left)
     }
 
     pub fn right() -> Self {
-        Self {
-            side: Right,
-            slot: html! { "" },
-            last: false,
-        }
-    }
-
-    pub fn last(mut self, last: bool) -> Self {
-        self.last = last;
-        self
-    }
-
-    pub fn slot(mut self, slot: impl Render) -> Self {
-        self.slot = slot.render();
-        self
+        Self::new(Right)
     }
 }
 
 impl Render for Row {
     fn render(&self) -> Markup {
-        let last_section_classes = if self.last { "h-grid-52" } else { "h-grid-68" };
+        let last_section_classes = if self.last {
+            class!("h-grid-52")
+        } else {
+            class!("h-grid-68")
+        };
 
         let side_classes = match self.side {
-            Left => "justify-start",
-            Right => "justify-end",
+            Left => class!("justify-start"),
+            Right => class!("justify-end"),
         };
 
         let section_classes = format!(
-            "relative flex flex-row px-grid-6 w-full {last_section_classes} {side_classes}"
+            "{} {last_section_classes} {side_classes}",
+            class!("relative flex flex-row px-grid-6 w-full")
         );
 
         html! {
@@ -57,10 +49,11 @@ impl Render for Row {
 
                 div
                     class={
-                        "flex flex-col w-grid-93 z-20 "
+                        (class!("flex flex-col w-grid-93 z-20"))
+                        " "
                         (match self.side {
                             Left => "",
-                            Right => "items-end",
+                            Right => class!("items-end"),
                         })
                     }
                 {
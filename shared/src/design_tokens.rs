@@ -0,0 +1,124 @@
+// The single source of truth for our colors, spacing, and other design
+// values. `include_tailwind!` generates the Tailwind theme from these, so
+// classes like `bg-slate` and `w-grid-52` always match what Rust uses.
+//
+// Tailwind only sees class names that appear literally in the source, so
+// in class strings prefer the generated classes, or theme() in arbitrary
+// values, like `shadow-[0_0_0_theme(colors.slate)]`. Use these constants
+// when a value has to be computed in Rust, like an inline style.
+
+use std::fmt::Display;
+
+/// A color from our palette, as a CSS hex color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColorToken(pub &'static str);
+
+impl Display for ColorToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub mod colors {
+    use super::ColorToken;
+
+    pub const slate: ColorToken = ColorToken("#283036"); // Background.
+    pub const dark: ColorToken = ColorToken("#1B2430"); // Dark background overlay.
+    pub const neutral: ColorToken = ColorToken("#E6E6E6");
+
+    pub mod petal {
+        use super::ColorToken;
+
+        pub const pink: ColorToken = ColorToken("#E189DC"); // The top of the petal gradient.
+        pub const lavender: ColorToken = ColorToken("#E189E2");
+        pub const salmon: ColorToken = ColorToken("#E189AB");
+        pub const orange: ColorToken = ColorToken("#E1A889");
+        pub const yellow: ColorToken = ColorToken("#E2D989");
+        pub const blue: ColorToken = ColorToken("#8997E1");
+        pub const purple: ColorToken = ColorToken("#BB89E2"); // Called barney purple in Figma.
+
+        pub const all: [(&str, ColorToken); 7] = [
+            ("pink", pink),
+            ("lavender", lavender),
+            ("salmon", salmon),
+            ("orange", orange),
+            ("yellow", yellow),
+            ("blue", blue),
+            ("purple", purple),
+        ];
+    }
+
+    pub const all: [(&str, ColorToken); 3] =
+        [("slate", slate), ("dark", dark), ("neutral", neutral)];
+}
+
+/// Our layout is based on a grid of this many columns across the viewport.
+/// `grid-N` spacing and font sizes are N columns wide.
+pub const grid_columns: u32 = 180;
+
+/// The width of `columns` grid columns, as a CSS length.
+pub fn grid(columns: i32) -> String {
+    format!("calc({}*100vw/{})", columns, grid_columns)
+}
+
+/// The corner radius of our cards and the cutouts between them.
+pub const tooth_radius_in_grid_columns: i32 = 10;
+
+pub fn tooth_radius() -> String {
+    grid(tooth_radius_in_grid_columns)
+}
+
+pub const font_families: [(&str, &str); 3] = [
+    ("fugi", "Fugi"),
+    ("aurora-grotesk", "Aurora Grotesk"),
+    ("clearface", "Clearface"),
+];
+
+/// The background of the main section of the home page.
+pub fn petal_gradient() -> String {
+    format!(
+        "linear-gradient(to bottom, {} 0%, {} 25%, {} 50%, {} 100%)",
+        colors::petal::pink,
+        colors::petal::salmon,
+        colors::petal::orange,
+        colors::petal::yellow
+    )
+}
+
+/// The `theme.extend` section of our Tailwind config, as JSON.
+/// See `assets/macro/tailwind.config.js`.
+#[cfg(feature = "build_time")]
+pub fn tailwind_theme_json() -> String {
+    use serde_json::{json, Map, Value};
+
+    let color_entries = |palette: &[(&str, ColorToken)]| -> Map<String, Value> {
+        palette
+            .iter()
+            .map(|(name, color)| (name.to_string(), json!(color.0)))
+            .collect()
+    };
+
+    let mut color_theme = color_entries(&colors::all);
+    color_theme.insert(
+        "petal".to_string(),
+        Value::Object(color_entries(&colors::petal::all)),
+    );
+
+    let grid_scale: Map<String, Value> = (0..=grid_columns as i32)
+        .map(|columns| (format!("grid-{}", columns), json!(grid(columns))))
+        .collect();
+
+    let font_family: Map<String, Value> = font_families
+        .iter()
+        .map(|(name, family)| (name.to_string(), json!([family])))
+        .collect();
+
+    json!({
+        "fontFamily": font_family,
+        "borderRadius": { "tooth": tooth_radius() },
+        "colors": color_theme,
+        "fontSize": grid_scale,
+        "spacing": grid_scale,
+    })
+    .to_string()
+}
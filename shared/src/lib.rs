@@ -2,6 +2,8 @@
 
 // use maud::{html, Markup};
 
+pub mod design_tokens;
+
 pub mod prelude;
 pub use self::prelude::*;
 
@@ -1,7 +1,7 @@
 use enum_iterator;
 use std::fmt::Display;
 
-#[derive(Clone, Copy, enum_iterator::Sequence)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, enum_iterator::Sequence)]
 pub enum Route {
     ArtbreederUserStory,
     BuildTime,